@@ -0,0 +1,116 @@
+//! Proc-macro front end for saphire-typegen, generating types from an actiondump at compile time.
+
+use std::path::PathBuf;
+use proc_macro::TokenStream;
+use quote::quote;
+use saphire_typegen::{gen::{generate_from_str, GenConfig, Rank, Section}, raw_types::LoadMode};
+use syn::{bracketed, parse::{Parse, ParseStream}, parse_macro_input, punctuated::Punctuated, Ident, LitStr, Token};
+
+/// The input to [`include_actiondump!`], a path followed by optional `key = [...]` or `key = value` settings.
+struct Input {
+    path: LitStr,
+    config: GenConfig,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse::<LitStr>()?;
+        let mut config = GenConfig::new();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            if key == "max_rank" {
+                let rank = input.parse::<Ident>()?;
+                let parsed = rank.to_string().parse::<Rank>()
+                    .map_err(|err| syn::Error::new(rank.span(), err))?;
+                config = config.max_rank(parsed);
+                continue;
+            }
+            if key == "load_mode" {
+                let mode = input.parse::<Ident>()?;
+                let parsed = mode.to_string().parse::<LoadMode>()
+                    .map_err(|err| syn::Error::new(mode.span(), err))?;
+                config = config.load_mode(parsed);
+                continue;
+            }
+            let content;
+            bracketed!(content in input);
+
+            match key.to_string().as_str() {
+                "imports" => {
+                    let imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    config = config.imports(imports.iter().map(LitStr::value));
+                }
+                "derives" => {
+                    for derive in Punctuated::<LitStr, Token![,]>::parse_terminated(&content)? {
+                        config = config.derive(derive.value());
+                    }
+                }
+                "sections" => {
+                    for section in Section::ALL {
+                        config = config.disable(section);
+                    }
+                    for section in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
+                        let parsed = section.to_string().parse::<Section>()
+                            .map_err(|err| syn::Error::new(section.span(), err))?;
+                        config = config.enable(parsed);
+                    }
+                }
+                _ => return Err(syn::Error::new(key.span(), "expected one of `imports`, `derives`, `sections`, `max_rank` or `load_mode`")),
+            }
+        }
+
+        Ok(Input { path, config })
+    }
+}
+
+/// Generates the types for an actiondump at compile time, expanding to one inline module per generated module.
+///
+/// The path is relative to the root of the crate using the macro. Generation can be tuned with
+/// `imports = ["..."]`, `derives = ["..."]`, `sections = [...]`, `max_rank = ...` and `load_mode = ...`, which mirror [`GenConfig`].
+/// Action modules import their argument types from Saphire-types' paths by default, anywhere else `imports` has to name
+/// the modules they're defined in.
+///
+/// ```ignore
+/// saphire_typegen_macros::include_actiondump!("actiondump.json", imports = ["crate::types::*"]);
+///
+/// saphire_typegen_macros::include_actiondump!(
+///     "actiondump.json",
+///     imports = ["my_crate::values::*"],
+///     derives = ["PartialEq"],
+///     sections = [Actions, GameValues],
+///     max_rank = Noble,
+///     load_mode = Strict,
+/// );
+/// ```
+#[proc_macro]
+pub fn include_actiondump(input: TokenStream) -> TokenStream {
+    let Input { path, config } = parse_macro_input!(input as Input);
+
+    let mut full_path = std::env::var("CARGO_MANIFEST_DIR").map(PathBuf::from).unwrap_or_default();
+    full_path.push(path.value());
+
+    let contents = match std::fs::read_to_string(&full_path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            let message = format!("failed to read {}: {}", full_path.display(), err);
+            return syn::Error::new(path.span(), message).to_compile_error().into();
+        }
+    };
+    let generated = match generate_from_str(&contents, &config) {
+        Ok(generated) => generated.inline(),
+        Err(err) => return syn::Error::new(path.span(), err).to_compile_error().into(),
+    };
+
+    // Including the dump makes cargo rebuild the crate whenever it changes.
+    let full_path = full_path.to_string_lossy().to_string();
+    quote!(
+        const _: &[u8] = include_bytes!(#full_path);
+        #generated
+    ).into()
+}
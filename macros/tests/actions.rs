@@ -0,0 +1,82 @@
+//! Generates the action modules against minimal stand-ins for Saphire-types' argument types.
+
+mod common;
+
+mod generated {
+    saphire_typegen_macros::include_actiondump!(
+        "../test_data/actiondump.json",
+        imports = ["crate::common::*"],
+        derives = ["Debug", "PartialEq"],
+        sections = [Actions],
+    );
+}
+
+use either::Either;
+use generated::{call_function::CallFunction, game_action::GameAction, player_action::{PlayerAction, PlayerActionName}, requirements::Rank, returns::Output, select_object::SelectObject, set_variable::SetVariable, tags};
+use common::{EntityType, Location, MiniMessage, Number, SelectPlayer, SpawnEgg, VariableLiteral};
+
+#[test]
+fn test_round_trip() {
+    let action = PlayerAction::SendMessage {
+        message_to_send: vec![MiniMessage("Hello".to_string()), MiniMessage("world".to_string())],
+        alignment_mode_tag: tags::AlignmentMode::Centered,
+        inherit_styles_tag: Default::default(),
+    };
+    let json = action.compile();
+    assert_eq!(json["args"]["items"].as_array().unwrap().len(), 4);
+    assert!(PlayerAction::from_json(&json).unwrap() == action);
+    assert!(generated::control::Control::from_json(&json).is_err());
+
+    let call = CallFunction::CallFunction { function_name: "setup".to_string() };
+    assert!(CallFunction::from_json(&call.compile()).unwrap() == call);
+}
+
+#[test]
+fn test_either_arguments() {
+    for mob_type in [Either::Left(SpawnEgg("zombie".to_string())), Either::Right(EntityType("zombie".to_string()))] {
+        let action = GameAction::SpawnMob { mob_type_: mob_type, spawn_location: Location("spawn".to_string()) };
+        assert!(GameAction::from_json(&action.compile()).unwrap() == action);
+    }
+}
+
+#[test]
+fn test_subaction() {
+    let action = SelectObject::PlayersCondition { subaction: SelectPlayer(serde_json::json!({"block": "if_player", "action": "IsNear"})) };
+    let json = action.compile();
+    assert_eq!(json["subaction"], "IsNear");
+    assert_eq!(SelectObject::from_json(&json).unwrap().compile(), json);
+}
+
+#[test]
+fn test_requirements() {
+    let action = PlayerAction::SetReducedDebugInfoEnabled { reduced_debug_info_enabled_tag: Default::default() };
+    assert_eq!(action.requirements().rank, Rank::Noble);
+    assert!(!action.requirements().allows(Rank::None));
+}
+
+#[test]
+fn test_from_name() {
+    assert_eq!(PlayerActionName::from_name("SendMessage"), Some(PlayerActionName::SendMessage));
+    assert_eq!(PlayerActionName::from_name("send message"), Some(PlayerActionName::SendMessage));
+    assert_eq!(PlayerActionName::from_name("MESSAGE"), Some(PlayerActionName::SendMessage));
+    assert_eq!(PlayerActionName::from_name("Join"), None);
+    assert_eq!(PlayerActionName::SendMessage.name(), "SendMessage");
+
+    let action = PlayerAction::SetReducedDebugInfoEnabled { reduced_debug_info_enabled_tag: Default::default() };
+    assert_eq!(action.action(), PlayerActionName::SetReducedDebugInfoEnabled);
+
+    assert!(tags::AlignmentMode::from_name("centered") == Some(tags::AlignmentMode::Centered));
+    assert!(tags::AlignmentMode::from_name("center") == Some(tags::AlignmentMode::Centered));
+    assert!(tags::AlignmentMode::from_name("Justified").is_none());
+}
+
+
+#[test]
+fn test_output() {
+    let action = SetVariable::Add {
+        variable_to_set: Output::new(VariableLiteral("total".to_string())),
+        numbers_to_add: vec![Number("1".to_string()), Number("2".to_string())],
+    };
+    assert_eq!(action.return_types()[0].type_name, "Number");
+    assert!(SetVariable::from_json(&action.compile()).unwrap() == action);
+}
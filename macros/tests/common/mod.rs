@@ -0,0 +1,76 @@
+//! Minimal stand-ins for Saphire-types' argument types, for the generated action modules to import.
+
+use either::Either;
+use serde_json::{json, Map, Value};
+pub use super::generated::decode::{DecodeError, FromItem};
+
+pub trait Json {
+    fn json(&self) -> Value;
+}
+
+macro_rules! arg_types {
+    ($($name:ident => $id:literal),*) => {$(
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name(pub String);
+
+        impl Json for $name {
+            fn json(&self) -> Value {
+                json!({"id": $id, "data": {"name": self.0}})
+            }
+        }
+
+        impl FromItem for $name {
+            fn from_item(item: &Value) -> Result<Self, DecodeError> {
+                match item["data"]["name"].as_str() {
+                    Some(name) if item["id"] == $id => Ok($name(name.to_string())),
+                    _ => Err(DecodeError::InvalidItem(item.to_string())),
+                }
+            }
+        }
+    )*};
+}
+
+arg_types!(Number => "num", MiniMessage => "comp", Location => "loc", SpawnEgg => "egg", EntityType => "ent", VariableLiteral => "var", AnyType => "any");
+
+impl<T: Json> Json for Vec<T> {
+    fn json(&self) -> Value {
+        Value::Array(self.iter().map(Json::json).collect())
+    }
+}
+
+impl<T: Json> Json for Option<T> {
+    fn json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, Json::json)
+    }
+}
+
+impl<L: Json, R: Json> Json for Either<L, R> {
+    fn json(&self) -> Value {
+        either::for_both!(self, value => value.json())
+    }
+}
+
+/// Lays out arguments from the first slot and tags from the last, as DiamondFire does.
+pub fn compile(args: Vec<Value>, tags: Vec<Map<String, Value>>) -> Vec<Value> {
+    let args = args.into_iter().flat_map(|arg| match arg {
+        Value::Null => Vec::new(),
+        Value::Array(values) => values,
+        value => vec![value],
+    });
+    let mut items = args.enumerate().map(|(slot, item)| json!({"item": item, "slot": slot})).collect::<Vec<_>>();
+    items.extend(tags.into_iter().enumerate().map(|(i, tag)| json!({"item": tag, "slot": 26 - i})));
+    items
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectPlayer(pub Value);
+
+impl SelectPlayer {
+    pub fn compile(&self) -> Value {
+        self.0.clone()
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(SelectPlayer(value.clone()))
+    }
+}
@@ -0,0 +1,9 @@
+//! Every section of a dump can be empty, which still has to expand to code that compiles.
+
+saphire_typegen_macros::include_actiondump!("../test_data/empty_actiondump.json");
+
+#[test]
+fn test_empty_sections() {
+    assert_eq!(block_kind::BlockKind::from_identifier("player_action"), None);
+    assert_eq!(game_value::GameValueTarget::default().name(), "Default");
+}
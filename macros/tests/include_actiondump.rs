@@ -0,0 +1,40 @@
+// The argument types are only named by the generated code here, the actions test constructs them.
+#[allow(dead_code)]
+mod common;
+
+mod generated {
+    saphire_typegen_macros::include_actiondump!(
+        "../test_data/actiondump.json",
+        imports = ["crate::common::*"],
+        load_mode = Strict,
+    );
+}
+
+use generated::*;
+
+#[test]
+fn test_actions() {
+    assert_eq!(player_action::PlayerActionName::SendMessage.name(), "SendMessage");
+    assert_eq!(game_action::GameActionName::from_name("SpawnMob"), Some(game_action::GameActionName::SpawnMob));
+}
+
+#[test]
+fn test_block_kind() {
+    assert_eq!(block_kind::BlockKind::PlayerAction.identifier(), "player_action");
+    assert_eq!(block_kind::BlockKind::from_identifier("set_var"), Some(block_kind::BlockKind::SetVariable));
+}
+
+#[test]
+fn test_game_value() {
+    let json = game_value::GameValue::CurrentHealth.json(game_value::GameValueTarget::Selection);
+    assert_eq!(json["id"], "g_val");
+    assert_eq!(json["data"]["type"], "Current Health");
+    assert_eq!(json["data"]["target"], "Selection");
+}
+
+#[test]
+fn test_sound() {
+    let json = sound::SoundKind::Pling.json(1.0, 2.0);
+    assert_eq!(json["data"]["sound"], "Pling");
+    assert_eq!(sound::SoundKind::ZombieHurt.category(), Some(sound::SoundCategory::Entity));
+}
@@ -0,0 +1,279 @@
+//! Compares two action dumps, to see what a DiamondFire update changed.
+
+use std::{collections::BTreeMap, fmt::Write};
+use crate::raw_types::{Action, ActionArg, ActionArgOptions, ActionDump, ActionTags};
+
+/// An action, by its codeblock and name as they appear in the dump.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ActionId {
+    /// The codeblock's name, eg. "PLAYER ACTION".
+    pub codeblock: String,
+    pub name: String,
+}
+
+impl ActionId {
+    fn of(action: &Action) -> Self {
+        Self { codeblock: action.codeblock_name.clone(), name: action.name.clone() }
+    }
+}
+
+/// A change to one of an action's arguments or tags. Argument indices skip the "OR" separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    ArgumentAdded { index: usize, arg_type: String },
+    ArgumentRemoved { index: usize, arg_type: String },
+    /// The argument's `type`, `plural` or `optional` changed.
+    ArgumentChanged { index: usize, field: &'static str, old: String, new: String },
+    TagAdded { tag: String },
+    TagRemoved { tag: String },
+    TagOptionAdded { tag: String, option: String },
+    TagOptionRemoved { tag: String, option: String },
+    TagDefaultChanged { tag: String, old: String, new: String },
+}
+
+/// The changes to an action present in both dumps, under its new name if it was renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionChanges {
+    pub action: ActionId,
+    pub changes: Vec<Change>,
+}
+
+/// Everything that changed between two dumps' actions, sorted by codeblock and action name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DumpDiff {
+    pub added: Vec<ActionId>,
+    pub removed: Vec<ActionId>,
+    /// Actions whose name changed, as the old id and the new name.
+    pub renamed: Vec<(ActionId, String)>,
+    pub changed: Vec<ActionChanges>,
+}
+
+/// Compares the actions of two dumps.
+///
+/// An action that's only in `new` is a rename of one only in `old` if it's in the same codeblock and
+/// either lists the old name among its aliases or has the same, non-empty, icon name.
+pub fn diff(old: &ActionDump, new: &ActionDump) -> DumpDiff {
+    let old_actions = old.actions.iter().map(|action| (ActionId::of(action), action)).collect::<BTreeMap<_, _>>();
+    let new_actions = new.actions.iter().map(|action| (ActionId::of(action), action)).collect::<BTreeMap<_, _>>();
+
+    let mut result = DumpDiff::default();
+    let mut pairs = Vec::new();
+    let mut added = new_actions.iter().filter(|(id, _)| !old_actions.contains_key(id)).collect::<Vec<_>>();
+    for (id, old_action) in &old_actions {
+        if let Some(new_action) = new_actions.get(id) {
+            pairs.push((id.clone(), *old_action, *new_action));
+            continue;
+        }
+        let renamed = added.iter().position(|(new_id, new_action)| {
+            new_id.codeblock == id.codeblock
+                && (new_action.aliases.contains(&id.name) || (!old_action.icon.name.is_empty() && new_action.icon.name == old_action.icon.name))
+        });
+        match renamed {
+            Some(i) => {
+                let (new_id, new_action) = added.remove(i);
+                result.renamed.push((id.clone(), new_id.name.clone()));
+                pairs.push((new_id.clone(), *old_action, *new_action));
+            }
+            None => result.removed.push(id.clone()),
+        }
+    }
+    result.added = added.into_iter().map(|(id, _)| id.clone()).collect();
+
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    for (action, old_action, new_action) in pairs {
+        let mut changes = diff_arguments(old_action, new_action);
+        changes.extend(diff_tags(&old_action.tags, &new_action.tags));
+        if !changes.is_empty() {
+            result.changed.push(ActionChanges { action, changes });
+        }
+    }
+    result
+}
+
+/// An action's arguments, without the "OR" separators between them.
+fn arguments(action: &Action) -> Vec<&ActionArg> {
+    action.icon.arguments().iter()
+        .filter_map(|arg| match arg {
+            ActionArgOptions::Arg(arg) => Some(arg),
+            ActionArgOptions::Text { .. } => None,
+        })
+        .collect()
+}
+
+fn diff_arguments(old: &Action, new: &Action) -> Vec<Change> {
+    let (old, new) = (arguments(old), arguments(new));
+    let mut changes = Vec::new();
+    for (index, (old, new)) in old.iter().zip(&new).enumerate() {
+        let fields = [
+            ("type", old.arg_type.clone(), new.arg_type.clone()),
+            ("plural", old.plural.to_string(), new.plural.to_string()),
+            ("optional", old.optional.to_string(), new.optional.to_string()),
+        ];
+        for (field, old, new) in fields {
+            if old != new {
+                changes.push(Change::ArgumentChanged { index, field, old, new });
+            }
+        }
+    }
+    for (index, arg) in old.iter().enumerate().skip(new.len()) {
+        changes.push(Change::ArgumentRemoved { index, arg_type: arg.arg_type.clone() });
+    }
+    for (index, arg) in new.iter().enumerate().skip(old.len()) {
+        changes.push(Change::ArgumentAdded { index, arg_type: arg.arg_type.clone() });
+    }
+    changes
+}
+
+fn diff_tags(old: &[ActionTags], new: &[ActionTags]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for old_tag in old {
+        let Some(new_tag) = new.iter().find(|tag| tag.name == old_tag.name) else {
+            changes.push(Change::TagRemoved { tag: old_tag.name.clone() });
+            continue;
+        };
+        for option in &old_tag.options {
+            if !new_tag.options.iter().any(|other| other.name == option.name) {
+                changes.push(Change::TagOptionRemoved { tag: old_tag.name.clone(), option: option.name.clone() });
+            }
+        }
+        for option in &new_tag.options {
+            if !old_tag.options.iter().any(|other| other.name == option.name) {
+                changes.push(Change::TagOptionAdded { tag: old_tag.name.clone(), option: option.name.clone() });
+            }
+        }
+        if old_tag.default_option != new_tag.default_option {
+            changes.push(Change::TagDefaultChanged { tag: old_tag.name.clone(), old: old_tag.default_option.clone(), new: new_tag.default_option.clone() });
+        }
+    }
+    for new_tag in new {
+        if !old.iter().any(|tag| tag.name == new_tag.name) {
+            changes.push(Change::TagAdded { tag: new_tag.name.clone() });
+        }
+    }
+    changes
+}
+
+impl DumpDiff {
+    /// Whether the dumps' actions are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Renders the diff as Markdown release notes, with a section for each kind of change that happened.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        if self.is_empty() {
+            out.push_str("No changes to actions.\n");
+            return out;
+        }
+
+        let lists = [("Added actions", &self.added), ("Removed actions", &self.removed)];
+        for (heading, actions) in lists {
+            if !actions.is_empty() {
+                let _ = writeln!(out, "## {}\n", heading);
+                for action in actions {
+                    let _ = writeln!(out, "- `{}` `{}`", action.codeblock, action.name);
+                }
+                out.push('\n');
+            }
+        }
+        if !self.renamed.is_empty() {
+            out.push_str("## Renamed actions\n\n");
+            for (action, name) in &self.renamed {
+                let _ = writeln!(out, "- `{}` `{}` → `{}`", action.codeblock, action.name, name);
+            }
+            out.push('\n');
+        }
+        if !self.changed.is_empty() {
+            out.push_str("## Changed actions\n\n");
+            for action in &self.changed {
+                let _ = writeln!(out, "### `{}` `{}`\n", action.action.codeblock, action.action.name);
+                for change in &action.changes {
+                    let _ = writeln!(out, "- {}", change_markdown(change));
+                }
+                out.push('\n');
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+}
+
+/// A change as a line of Markdown, numbering arguments from 1.
+fn change_markdown(change: &Change) -> String {
+    match change {
+        Change::ArgumentAdded { index, arg_type } => format!("Added argument {} (`{}`)", index + 1, arg_type),
+        Change::ArgumentRemoved { index, arg_type } => format!("Removed argument {} (`{}`)", index + 1, arg_type),
+        Change::ArgumentChanged { index, field, old, new } => format!("Argument {} `{}` changed from `{}` to `{}`", index + 1, field, old, new),
+        Change::TagAdded { tag } => format!("Added tag \"{}\"", tag),
+        Change::TagRemoved { tag } => format!("Removed tag \"{}\"", tag),
+        Change::TagOptionAdded { tag, option } => format!("Tag \"{}\" gained option \"{}\"", tag, option),
+        Change::TagOptionRemoved { tag, option } => format!("Tag \"{}\" lost option \"{}\"", tag, option),
+        Change::TagDefaultChanged { tag, old, new } => format!("Tag \"{}\" default changed from \"{}\" to \"{}\"", tag, old, new),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ACTION_DUMP: &str = include_str!("../test_data/actiondump.json");
+
+    fn load() -> ActionDump {
+        serde_json::from_str(ACTION_DUMP).unwrap()
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let diff = diff(&load(), &load());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_markdown(), "No changes to actions.\n");
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = load();
+        let mut new = load();
+
+        let send_message = new.actions.iter_mut().find(|action| action.name == "SendMessage").unwrap();
+        let ActionArgOptions::Arg(arg) = &mut send_message.icon.args.as_mut().unwrap().arguments[0] else {
+            panic!("SendMessage's first argument is an argument");
+        };
+        arg.plural = false;
+        let tag = &mut send_message.tags[0];
+        let removed_option = tag.options.remove(0).name;
+        let tag_name = tag.name.clone();
+
+        let renamed = new.actions.iter_mut().find(|action| action.name == "SetReducedDebug").unwrap();
+        renamed.name = "SetReducedDebugInfo".to_string();
+        let removed = new.actions.iter().position(|action| action.name == "Join").unwrap();
+        new.actions.remove(removed);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.removed, [ActionId { codeblock: "PLAYER EVENT".to_string(), name: "Join".to_string() }]);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.renamed, [(ActionId { codeblock: "PLAYER ACTION".to_string(), name: "SetReducedDebug".to_string() }, "SetReducedDebugInfo".to_string())]);
+
+        let changes = &diff.changed.iter().find(|action| action.action.name == "SendMessage").unwrap().changes;
+        assert!(changes.contains(&Change::ArgumentChanged { index: 0, field: "plural", old: "true".to_string(), new: "false".to_string() }));
+        assert!(changes.contains(&Change::TagOptionRemoved { tag: tag_name, option: removed_option }));
+
+        assert_eq!(diff.to_markdown(), "\
+## Removed actions
+
+- `PLAYER EVENT` `Join`
+
+## Renamed actions
+
+- `PLAYER ACTION` `SetReducedDebug` → `SetReducedDebugInfo`
+
+## Changed actions
+
+### `PLAYER ACTION` `SendMessage`
+
+- Argument 1 `plural` changed from `true` to `false`
+- Tag \"Alignment Mode\" lost option \"Regular\"
+");
+    }
+}
@@ -0,0 +1,92 @@
+//! Compares a dump's json with the raw types, to notice DiamondFire's schema changes before parsing breaks.
+
+use std::{collections::BTreeMap, fmt};
+use serde_json::Value;
+use crate::load::{RawType, Walker};
+
+pub use crate::load::{Drift, DriftKind};
+
+/// Every difference between a dump and the raw types, in the order they appear in the dump.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftReport {
+    pub drift: Vec<Drift>,
+}
+
+impl DriftReport {
+    /// Whether the dump matches the raw types exactly.
+    pub fn is_empty(&self) -> bool {
+        self.drift.is_empty()
+    }
+
+    /// The differences grouped by the raw type they belong to.
+    pub fn by_type(&self) -> BTreeMap<&'static str, Vec<&Drift>> {
+        let mut by_type = BTreeMap::<_, Vec<_>>::new();
+        for drift in &self.drift {
+            by_type.entry(drift.raw_type).or_default().push(drift);
+        }
+        by_type
+    }
+}
+
+impl fmt::Display for DriftReport {
+    /// Lists the differences under the raw type they belong to.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no drift");
+        }
+        for (raw_type, drift) in self.by_type() {
+            writeln!(f, "{}:", raw_type)?;
+            for drift in drift {
+                match &drift.kind {
+                    DriftKind::Unmodelled => writeln!(f, "  unmodelled field `{}`", drift.path)?,
+                    DriftKind::TypeMismatch { expected, found } => writeln!(f, "  `{}` is a {}, expected a {}", drift.path, found, expected)?,
+                    DriftKind::Missing => writeln!(f, "  missing field `{}`", drift.path)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Walks the json of a `T`, usually an [`ActionDump`](crate::raw_types::ActionDump), reporting where it differs from the raw types.
+pub fn detect<T: RawType>(value: &Value) -> DriftReport {
+    let mut walker = Walker::detecting();
+    T::walk(&mut value.clone(), &mut walker);
+    DriftReport { drift: walker.into_drift() }
+}
+
+/// Like [`detect`], for json that hasn't been parsed yet.
+pub fn detect_str<T: RawType>(json: &str) -> Result<DriftReport, serde_json::Error> {
+    Ok(detect::<T>(&serde_json::from_str(json)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::raw_types::ActionDump;
+
+    const ACTION_DUMP: &str = include_str!("../test_data/actiondump.json");
+
+    #[test]
+    fn test_no_drift() {
+        let report = detect_str::<ActionDump>(ACTION_DUMP).unwrap();
+        assert!(report.is_empty(), "{}", report);
+    }
+
+    #[test]
+    fn test_drift() {
+        let mut dump = serde_json::from_str::<Value>(ACTION_DUMP).unwrap();
+        let action = &mut dump["actions"][1];
+        action["shiny"] = Value::Bool(true);
+        action["icon"]["arguments"][0]["plural"] = Value::from("no");
+        action["icon"]["arguments"][0].as_object_mut().unwrap().remove("notes");
+        action.as_object_mut().unwrap().remove("aliases");
+
+        let report = detect::<ActionDump>(&dump);
+        let by_type = report.by_type();
+        assert_eq!(by_type.keys().copied().collect::<Vec<_>>(), ["Action", "ActionArg"]);
+        assert_eq!(by_type["Action"][0], &Drift { raw_type: "Action", path: "actions[1].shiny".to_string(), kind: DriftKind::Unmodelled });
+        assert_eq!(by_type["ActionArg"][0].kind, DriftKind::TypeMismatch { expected: "bool", found: "string" });
+        assert_eq!(by_type["ActionArg"][1].kind, DriftKind::Missing);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{collections::BTreeMap, fmt};
+use proc_macro2::Span;
+use crate::raw_types::ActionDump;
+use super::{generate, GenConfig, GenError};
+
+/// How an item of the generated API changed between two dumps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiChangeKind {
+    EnumAdded,
+    EnumRemoved,
+    /// A new variant, which breaks exhaustive matches since the generated enums aren't `#[non_exhaustive]`.
+    VariantAdded,
+    VariantRemoved,
+    /// A variant went from having named fields to none or the other way around.
+    VariantShapeChanged,
+    FieldAdded { ty: String },
+    FieldRemoved { ty: String },
+    /// A field in the same position with the same type got a new name, eg. because its description changed.
+    FieldRenamed { new: String },
+    FieldTypeChanged { old: String, new: String },
+}
+
+impl ApiChangeKind {
+    /// Whether code written against the old API can stop compiling.
+    pub fn is_breaking(&self) -> bool {
+        !matches!(self, ApiChangeKind::EnumAdded)
+    }
+}
+
+/// A change to a single enum, variant or field of the generated code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    /// The item as it's named in the old API, or the new one if it was added, eg. `player_action::PlayerAction::SendMessage::message`.
+    pub path: String,
+    pub kind: ApiChangeKind,
+}
+
+/// How the generated API changes from one dump to the next, from [`api_changes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    /// Every change, ordered by module, enum, variant and field.
+    pub changes: Vec<ApiChange>,
+}
+
+impl CompatReport {
+    pub fn breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|change| change.kind.is_breaking())
+    }
+
+    pub fn non_breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|change| !change.kind.is_breaking())
+    }
+
+    /// Whether code written against the old API still compiles against the new one.
+    pub fn is_compatible(&self) -> bool {
+        self.breaking().next().is_none()
+    }
+}
+
+impl fmt::Display for CompatReport {
+    /// Lists the breaking changes, then the others.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections: [(&str, Vec<&ApiChange>); 2] = [("Breaking", self.breaking().collect()), ("Non-breaking", self.non_breaking().collect())];
+        for (heading, changes) in sections {
+            writeln!(f, "{} changes: {}", heading, changes.len())?;
+            for change in changes {
+                match &change.kind {
+                    ApiChangeKind::EnumAdded => writeln!(f, "  added enum {}", change.path)?,
+                    ApiChangeKind::EnumRemoved => writeln!(f, "  removed enum {}", change.path)?,
+                    ApiChangeKind::VariantAdded => writeln!(f, "  added variant {}", change.path)?,
+                    ApiChangeKind::VariantRemoved => writeln!(f, "  removed variant {}", change.path)?,
+                    ApiChangeKind::VariantShapeChanged => writeln!(f, "  changed the fields of {}", change.path)?,
+                    ApiChangeKind::FieldAdded { ty } => writeln!(f, "  added field {}: {}", change.path, ty)?,
+                    ApiChangeKind::FieldRemoved { ty } => writeln!(f, "  removed field {}: {}", change.path, ty)?,
+                    ApiChangeKind::FieldRenamed { new } => writeln!(f, "  renamed field {} to {}", change.path, new)?,
+                    ApiChangeKind::FieldTypeChanged { old, new } => writeln!(f, "  changed field {} from {} to {}", change.path, old, new)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A variant's named fields and their types, in order, or `None` for a unit or tuple variant.
+type Fields = Option<Vec<(String, String)>>;
+
+/// Every public enum of the generated code, by path, with their variants.
+type Api = BTreeMap<String, BTreeMap<String, Fields>>;
+
+/// Generates the code for both dumps with `config` and compares their enums, variants and fields.
+pub fn api_changes(old: &ActionDump, new: &ActionDump, config: &GenConfig) -> Result<CompatReport, GenError> {
+    let old = api(old, config)?;
+    let new = api(new, config)?;
+
+    let mut changes = Vec::new();
+    let mut change = |path: String, kind| changes.push(ApiChange { path, kind });
+    for (enum_path, old_variants) in &old {
+        let Some(new_variants) = new.get(enum_path) else {
+            change(enum_path.clone(), ApiChangeKind::EnumRemoved);
+            continue;
+        };
+        for (variant, old_fields) in old_variants {
+            let path = format!("{}::{}", enum_path, variant);
+            match (old_fields, new_variants.get(variant)) {
+                (_, None) => change(path, ApiChangeKind::VariantRemoved),
+                (Some(old_fields), Some(Some(new_fields))) => {
+                    for (index, (name, ty)) in old_fields.iter().enumerate() {
+                        let field_path = format!("{}::{}", path, name);
+                        match new_fields.iter().find(|(new_name, _)| new_name == name) {
+                            Some((_, new_ty)) if new_ty != ty => change(field_path, ApiChangeKind::FieldTypeChanged { old: ty.clone(), new: new_ty.clone() }),
+                            Some(_) => {}
+                            None => match new_fields.get(index) {
+                                Some((new_name, new_ty)) if new_ty == ty && !old_fields.iter().any(|(name, _)| name == new_name) => {
+                                    change(field_path, ApiChangeKind::FieldRenamed { new: new_name.clone() });
+                                }
+                                _ => change(field_path, ApiChangeKind::FieldRemoved { ty: ty.clone() }),
+                            },
+                        }
+                    }
+                    for (index, (name, ty)) in new_fields.iter().enumerate() {
+                        let renamed = old_fields.get(index)
+                            .is_some_and(|(old_name, old_ty)| old_ty == ty && !new_fields.iter().any(|(name, _)| name == old_name));
+                        if !renamed && !old_fields.iter().any(|(old_name, _)| old_name == name) {
+                            change(format!("{}::{}", path, name), ApiChangeKind::FieldAdded { ty: ty.clone() });
+                        }
+                    }
+                }
+                (None, Some(None)) => {}
+                (_, Some(_)) => change(path, ApiChangeKind::VariantShapeChanged),
+            }
+        }
+        for variant in new_variants.keys().filter(|variant| !old_variants.contains_key(*variant)) {
+            change(format!("{}::{}", enum_path, variant), ApiChangeKind::VariantAdded);
+        }
+    }
+    for enum_path in new.keys().filter(|enum_path| !old.contains_key(*enum_path)) {
+        change(enum_path.clone(), ApiChangeKind::EnumAdded);
+    }
+
+    Ok(CompatReport { changes })
+}
+
+/// The public enums of the code generated for `dump`.
+fn api(dump: &ActionDump, config: &GenConfig) -> Result<Api, GenError> {
+    let mut api = Api::new();
+    for module in generate(dump, config)?.modules {
+        let file = syn::parse2::<syn::File>(module.code)
+            .map_err(|source| GenError::Syntax { module: module.name.clone(), source })?;
+        for item in file.items {
+            let syn::Item::Enum(item) = item else {
+                continue;
+            };
+            if !matches!(item.vis, syn::Visibility::Public(_)) {
+                continue;
+            }
+            let variants = item.variants.into_iter().map(|variant| {
+                let fields = match variant.fields {
+                    syn::Fields::Named(fields) => Some(fields.named.into_iter()
+                        .map(|field| (field.ident.map(|ident| ident.to_string()).unwrap_or_default(), type_string(field.ty)))
+                        .collect()),
+                    syn::Fields::Unnamed(_) | syn::Fields::Unit => None,
+                };
+                (variant.ident.to_string(), fields)
+            });
+            api.insert(format!("{}::{}", module.name, item.ident), variants.collect());
+        }
+    }
+    Ok(api)
+}
+
+/// A type as it's written in the formatted code, eg. `Option<Number>`.
+fn type_string(ty: syn::Type) -> String {
+    // Only whole files can be formatted, so the type is formatted as an alias.
+    let alias = syn::ItemType {
+        attrs: Vec::new(),
+        vis: syn::Visibility::Inherited,
+        type_token: Default::default(),
+        ident: syn::Ident::new("T", Span::call_site()),
+        generics: Default::default(),
+        eq_token: Default::default(),
+        ty: Box::new(ty),
+        semi_token: Default::default(),
+    };
+    let source = prettier_please::unparse(&syn::File { shebang: None, attrs: Vec::new(), items: vec![syn::Item::Type(alias)] });
+    source.trim().trim_start_matches("type T = ").trim_end_matches(';').to_string()
+}
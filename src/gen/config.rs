@@ -0,0 +1,185 @@
+use std::{collections::{BTreeMap, BTreeSet}, str::FromStr};
+use crate::raw_types::LoadMode;
+
+/// The parts of the dump code can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    /// A module per codeblock with an enum of its actions.
+    Actions,
+    /// The `BlockKind` enum.
+    BlockKind,
+    /// The `GameValue` enum.
+    GameValues,
+    /// The `ParticleKind` enum.
+    Particles,
+    /// The `SoundKind` enum.
+    Sounds,
+    /// The `PotionKind` enum.
+    Potions,
+}
+
+impl Section {
+    pub const ALL: [Section; 6] = [Section::Actions, Section::BlockKind, Section::GameValues, Section::Particles, Section::Sounds, Section::Potions];
+}
+
+impl FromStr for Section {
+    type Err = String;
+
+    /// Parses a section from its variant name, in either case, eg. `GameValues` or `game_values`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace('_', "");
+        Section::ALL.into_iter()
+            .find(|section| format!("{:?}", section).eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("unknown section {:?}", s))
+    }
+}
+
+/// The ranks DiamondFire locks actions behind, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Rank {
+    /// Available to everyone.
+    #[default]
+    None,
+    Noble,
+    Emperor,
+    Mythic,
+    Overlord,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 5] = [Rank::None, Rank::Noble, Rank::Emperor, Rank::Mythic, Rank::Overlord];
+}
+
+impl FromStr for Rank {
+    type Err = String;
+
+    /// Parses a rank from its name, in either case. The actiondump leaves the rank empty for actions anyone can use.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Rank::None);
+        }
+        Rank::ALL.into_iter()
+            .find(|rank| format!("{:?}", rank).eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown rank {:?}", s))
+    }
+}
+
+/// How generated modules are laid out on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OutputLayout {
+    /// A `{module}.rs` file per module, declaring them is left to the user.
+    #[default]
+    Files,
+    /// A `{module}.rs` file per module along with a `mod.rs` declaring all of them.
+    FilesWithMod,
+    /// Every module inline in a single file with the given name.
+    SingleFile(String),
+}
+
+/// Options for [`gen_with_config`](super::gen_with_config), built up from [`GenConfig::new`].
+///
+/// ```
+/// use saphire_typegen::gen::{GenConfig, OutputLayout, Rank, Section};
+///
+/// let config = GenConfig::new()
+///     .imports(["my_crate::values::*"])
+///     .derive("PartialEq")
+///     .module_name("player_action", "player")
+///     .layout(OutputLayout::FilesWithMod)
+///     .disable(Section::Sounds)
+///     .max_rank(Rank::Noble);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GenConfig {
+    pub(super) imports: Vec<String>,
+    pub(super) derives: Vec<String>,
+    pub(super) module_names: BTreeMap<String, String>,
+    pub(super) layout: OutputLayout,
+    pub(super) sections: BTreeSet<Section>,
+    pub(super) max_rank: Option<Rank>,
+    pub(super) load_mode: LoadMode,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        Self {
+            imports: vec![
+                "crate::types::*".to_string(),
+                "crate::block::block_types::subactions::*".to_string(),
+            ],
+            derives: Vec::new(),
+            module_names: BTreeMap::new(),
+            layout: OutputLayout::default(),
+            sections: Section::ALL.into_iter().collect(),
+            max_rank: None,
+            load_mode: LoadMode::default(),
+        }
+    }
+}
+
+impl GenConfig {
+    /// The configuration [`gen_types`](super::gen_types) uses, generating everything for Saphire-types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the paths action modules import argument types and subactions from, eg. `crate::types::*`.
+    pub fn imports<I: IntoIterator<Item = S>, S: Into<String>>(mut self, imports: I) -> Self {
+        self.imports = imports.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a path for action modules to import.
+    pub fn import<S: Into<String>>(mut self, import: S) -> Self {
+        self.imports.push(import.into());
+        self
+    }
+
+    /// Adds a derive to every action and tag enum, eg. `PartialEq` or `serde::Serialize`.
+    pub fn derive<S: Into<String>>(mut self, derive: S) -> Self {
+        self.derives.push(derive.into());
+        self
+    }
+
+    /// Writes the module that would be called `default` as `name` instead, eg. `player_action` as `player`.
+    pub fn module_name<S: Into<String>, N: Into<String>>(mut self, default: S, name: N) -> Self {
+        self.module_names.insert(default.into(), name.into());
+        self
+    }
+
+    pub fn layout(mut self, layout: OutputLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn enable(mut self, section: Section) -> Self {
+        self.sections.insert(section);
+        self
+    }
+
+    pub fn disable(mut self, section: Section) -> Self {
+        self.sections.remove(&section);
+        self
+    }
+
+    /// Leaves out actions that need a higher rank than `rank`, eg. `Rank::None` only keeps actions anyone can use.
+    pub fn max_rank(mut self, rank: Rank) -> Self {
+        self.max_rank = Some(rank);
+        self
+    }
+
+    /// How missing and unknown fields in the dump are treated when it's read from json, eg. `LoadMode::Strict` to fail on schema additions.
+    pub fn load_mode(mut self, mode: LoadMode) -> Self {
+        self.load_mode = mode;
+        self
+    }
+
+    pub fn is_enabled(&self, section: Section) -> bool {
+        self.sections.contains(&section)
+    }
+
+    /// The name a module is written as, after any renaming.
+    pub fn resolve_module_name(&self, default: &str) -> String {
+        self.module_names.get(default).cloned().unwrap_or_else(|| default.to_string())
+    }
+}
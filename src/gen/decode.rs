@@ -0,0 +1,161 @@
+use proc_macro2::token_stream;
+use quote::quote;
+
+/// Generates the `decode` module, the support code the generated `from_json` functions are built on.
+/// Argument types such as `Number` or `Text` take part in decoding by implementing its `FromItem` trait.
+pub(super) fn gen_decode_module() -> token_stream::TokenStream {
+    quote!(
+        use either::Either;
+        use serde_json::Value;
+
+        /// Why a block or tag couldn't be turned back into its generated type.
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum DecodeError {
+            /// A field the json needs is missing or has the wrong type.
+            MissingField(&'static str),
+            /// The json is for a different kind of block.
+            WrongBlock { expected: &'static str, found: String },
+            /// The block's action isn't one of the block's actions.
+            UnknownAction { block: &'static str, action: String },
+            /// The json is for a different tag.
+            WrongTag { expected: &'static str, found: String },
+            /// A tag the action needs is missing.
+            MissingTag { action: &'static str, tag: &'static str },
+            /// The block has a tag its action doesn't have.
+            UnexpectedTag { action: &'static str, tag: String },
+            /// The tag's option isn't one of the tag's options.
+            UnknownTagOption { tag: &'static str, option: String },
+            /// The block has no item for a required argument.
+            MissingArgument { action: &'static str },
+            /// The block has an item left over after every argument was read, at the given slot.
+            UnexpectedArgument { action: &'static str, slot: u64 },
+            /// An item isn't valid for the type it's read as.
+            InvalidItem(String),
+        }
+
+        impl std::fmt::Display for DecodeError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    DecodeError::MissingField(field) => write!(f, "missing field `{}`", field),
+                    DecodeError::WrongBlock { expected, found } => write!(f, "expected a {} block, found {}", expected, found),
+                    DecodeError::UnknownAction { block, action } => write!(f, "{} has no action {:?}", block, action),
+                    DecodeError::WrongTag { expected, found } => write!(f, "expected tag {:?}, found {:?}", expected, found),
+                    DecodeError::MissingTag { action, tag } => write!(f, "{} is missing tag {:?}", action, tag),
+                    DecodeError::UnexpectedTag { action, tag } => write!(f, "{} has no tag {:?}", action, tag),
+                    DecodeError::UnknownTagOption { tag, option } => write!(f, "{:?} is not an option of tag {:?}", option, tag),
+                    DecodeError::MissingArgument { action } => write!(f, "{} is missing an argument", action),
+                    DecodeError::UnexpectedArgument { action, slot } => write!(f, "{} has an unexpected argument in slot {}", action, slot),
+                    DecodeError::InvalidItem(message) => write!(f, "invalid item: {}", message),
+                }
+            }
+        }
+
+        impl std::error::Error for DecodeError {}
+
+        /// Types that can be read back from an item in a block's arguments.
+        pub trait FromItem: Sized {
+            fn from_item(item: &Value) -> Result<Self, DecodeError>;
+        }
+
+        impl<L: FromItem, R: FromItem> FromItem for Either<L, R> {
+            fn from_item(item: &Value) -> Result<Self, DecodeError> {
+                L::from_item(item).map(Either::Left).or_else(|_| R::from_item(item).map(Either::Right))
+            }
+        }
+
+        /// Checks that `value` is a block with the given identifier and returns its action.
+        /// Dynamic blocks have no action, they're reported as `dynamic`.
+        pub fn block_action<'a>(value: &'a Value, block: &'static str) -> Result<&'a str, DecodeError> {
+            let found = value.get("block").and_then(Value::as_str).ok_or(DecodeError::MissingField("block"))?;
+            if found != block {
+                return Err(DecodeError::WrongBlock { expected: block, found: found.to_string() });
+            }
+            match value.get("action").and_then(Value::as_str) {
+                Some(action) => Ok(action),
+                None if value.get("data").is_some() => Ok("dynamic"),
+                None => Err(DecodeError::MissingField("action")),
+            }
+        }
+
+        /// Reads the arguments and tags of a block, in slot order.
+        pub struct ArgReader<'a> {
+            action: &'static str,
+            args: Vec<(u64, &'a Value)>,
+            tags: Vec<&'a Value>,
+            next: usize,
+        }
+
+        impl<'a> ArgReader<'a> {
+            pub fn new(value: &'a Value, action: &'static str) -> Result<Self, DecodeError> {
+                let items = value.get("args")
+                    .and_then(|args| args.get("items"))
+                    .and_then(Value::as_array)
+                    .ok_or(DecodeError::MissingField("args.items"))?;
+
+                let mut args = Vec::new();
+                let mut tags = Vec::new();
+                for item in items {
+                    let slot = item.get("slot").and_then(Value::as_u64).ok_or(DecodeError::MissingField("slot"))?;
+                    let item = item.get("item").ok_or(DecodeError::MissingField("item"))?;
+                    if item.get("id").and_then(Value::as_str) == Some("bl_tag") {
+                        tags.push(item);
+                    }
+                    else {
+                        args.push((slot, item));
+                    }
+                }
+                args.sort_by_key(|(slot, _)| *slot);
+
+                Ok(Self { action, args, tags, next: 0 })
+            }
+
+            /// Reads an argument that must be present.
+            pub fn required<T: FromItem>(&mut self) -> Result<T, DecodeError> {
+                self.optional()?.ok_or(DecodeError::MissingArgument { action: self.action })
+            }
+
+            /// Reads the next argument if it's a `T`.
+            pub fn optional<T: FromItem>(&mut self) -> Result<Option<T>, DecodeError> {
+                let Some((_, item)) = self.args.get(self.next) else {
+                    return Ok(None);
+                };
+                match T::from_item(item) {
+                    Ok(value) => {
+                        self.next += 1;
+                        Ok(Some(value))
+                    }
+                    Err(_) => Ok(None),
+                }
+            }
+
+            /// Reads arguments for as long as they're `T`s.
+            pub fn plural<T: FromItem>(&mut self) -> Result<Vec<T>, DecodeError> {
+                let mut values = Vec::new();
+                while let Some(value) = self.optional()? {
+                    values.push(value);
+                }
+                Ok(values)
+            }
+
+            /// Takes the tag with the given name.
+            pub fn tag(&mut self, name: &'static str) -> Result<&'a Value, DecodeError> {
+                let index = self.tags.iter()
+                    .position(|tag| tag.pointer("/data/tag").and_then(Value::as_str) == Some(name))
+                    .ok_or(DecodeError::MissingTag { action: self.action, tag: name })?;
+                Ok(self.tags.remove(index))
+            }
+
+            /// Checks every argument and tag was read.
+            pub fn finish(self) -> Result<(), DecodeError> {
+                if let Some((slot, _)) = self.args.get(self.next) {
+                    return Err(DecodeError::UnexpectedArgument { action: self.action, slot: *slot });
+                }
+                if let Some(tag) = self.tags.first() {
+                    let tag = tag.pointer("/data/tag").and_then(Value::as_str).unwrap_or_default();
+                    return Err(DecodeError::UnexpectedTag { action: self.action, tag: tag.to_string() });
+                }
+                Ok(())
+            }
+        }
+    )
+}
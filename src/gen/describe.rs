@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use proc_macro2::token_stream;
+use crate::raw_types::ActionDump;
+use super::{generate, group_actions, module_name, snake_to_camel_case, GenConfig, GenError};
+
+/// The generated code for the actions named `name`, ie. their variant of the block enum along with the tag enums they use.
+/// `name` is matched against both the dump's name for the action and its variant name, ignoring case, so there's one
+/// description for every block with an action of that name.
+pub fn describe_action(dump: &ActionDump, config: &GenConfig, name: &str) -> Result<Vec<String>, GenError> {
+    let mut matches = Vec::new();
+    for (_, codeblock, actions) in group_actions(dump, config)? {
+        for ((action, _), variant) in actions {
+            if action.name.eq_ignore_ascii_case(name) || variant.eq_ignore_ascii_case(name) {
+                let module = module_name(&codeblock.name);
+                matches.push((config.resolve_module_name(&module), snake_to_camel_case(&module), variant));
+            }
+        }
+    }
+    if matches.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let modules = generate(dump, config)?.tokens();
+    let tags_module = config.resolve_module_name("tags");
+    let mut descriptions = Vec::new();
+    for (module, enum_name, variant) in matches {
+        // The action's variant is kept in its enum, which shows the block it belongs to.
+        let Some(mut block) = enums(&modules, &module)?.into_iter().find(|block| block.ident == enum_name) else {
+            continue;
+        };
+        block.variants = std::mem::take(&mut block.variants).into_iter().filter(|other| other.ident == variant).collect();
+        let source = unparse(vec![syn::Item::Enum(block)]);
+
+        let words = source.split(|c: char| !c.is_alphanumeric() && c != '_').collect::<Vec<_>>();
+        let tags = enums(&modules, &tags_module)?.into_iter()
+            .filter(|tag| words.contains(&tag.ident.to_string().as_str()))
+            .map(syn::Item::Enum)
+            .collect::<Vec<_>>();
+        let tags = if tags.is_empty() { String::new() } else { format!("\n{}", unparse(tags)) };
+
+        descriptions.push(format!("// {}\n{}{}", module, source, tags));
+    }
+    Ok(descriptions)
+}
+
+/// The enums of a generated module, parsed afresh since syn's types can't be cloned.
+fn enums(modules: &BTreeMap<String, token_stream::TokenStream>, module: &str) -> Result<Vec<syn::ItemEnum>, GenError> {
+    let Some(code) = modules.get(module) else {
+        return Ok(Vec::new());
+    };
+    let file = syn::parse2::<syn::File>(code.clone())
+        .map_err(|source| GenError::Syntax { module: module.to_string(), source })?;
+    Ok(file.items.into_iter()
+        .filter_map(|item| match item {
+            syn::Item::Enum(item) => Some(item),
+            _ => None,
+        })
+        .collect())
+}
+
+fn unparse(items: Vec<syn::Item>) -> String {
+    prettier_please::unparse(&syn::File { shebang: None, attrs: Vec::new(), items })
+}
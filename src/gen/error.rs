@@ -0,0 +1,119 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+/// Points at the actiondump entry an error was raised for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Index of the offending action in the dump's `actions` array, if the error belongs to one.
+    pub index: Option<usize>,
+    /// Name of the offending action, if the error belongs to one.
+    pub action: Option<String>,
+    /// JSON path of the offending entry, e.g. `actions[12].icon.arguments[3].type`.
+    pub path: String,
+}
+
+impl Location {
+    /// A location that isn't tied to a single action.
+    pub fn root(path: &str) -> Self {
+        Self {
+            index: None,
+            action: None,
+            path: path.to_string(),
+        }
+    }
+
+    /// The location of the action at `index` in the dump's `actions` array.
+    pub fn action(index: usize, name: &str) -> Self {
+        Self {
+            index: Some(index),
+            action: Some(name.to_string()),
+            path: format!("actions[{}]", index),
+        }
+    }
+
+    /// Returns this location with `field` appended to its JSON path.
+    pub fn join(&self, field: &str) -> Self {
+        let mut location = self.clone();
+        location.path = format!("{}.{}", self.path, field);
+        location
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.index, &self.action) {
+            (Some(index), Some(action)) => write!(f, "action #{} ({:?}) at `{}`", index, action, self.path),
+            (Some(index), None) => write!(f, "action #{} at `{}`", index, self.path),
+            _ => write!(f, "`{}`", self.path),
+        }
+    }
+}
+
+/// Everything that can go wrong while generating types from an actiondump.
+#[derive(Debug)]
+pub enum GenError {
+    /// Reading the actiondump or writing a generated module failed.
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The actiondump, or one of its entries, isn't valid JSON for the expected type.
+    Json {
+        location: Location,
+        source: serde_json::Error,
+    },
+    /// The actiondump is valid JSON but doesn't have the expected shape.
+    Schema {
+        location: Location,
+        message: String,
+    },
+    /// An action belongs to a codeblock the generator doesn't know about.
+    UnknownCodeblock {
+        location: Location,
+        codeblock: String,
+    },
+    /// An argument has a type with no Rust equivalent.
+    UnknownArgType {
+        location: Location,
+        arg_type: String,
+    },
+    /// Two generated items would end up with the same identifier.
+    IdentCollision {
+        location: Location,
+        ident: String,
+    },
+    /// The [`GenConfig`](super::GenConfig) has an invalid import, derive or module name.
+    Config {
+        message: String,
+    },
+    /// The generated code for a module couldn't be parsed back, this is a bug in the generator.
+    Syntax {
+        module: String,
+        source: syn::Error,
+    },
+}
+
+impl fmt::Display for GenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenError::Io { path, source } => write!(f, "io error on {}: {}", path.display(), source),
+            GenError::Json { location, source } => write!(f, "invalid json for {}: {}", location, source),
+            GenError::Schema { location, message } => write!(f, "unexpected actiondump layout at {}: {}", location, message),
+            GenError::UnknownCodeblock { location, codeblock } => write!(f, "unknown codeblock {:?} for {}", codeblock, location),
+            GenError::UnknownArgType { location, arg_type } => write!(f, "unknown argument type {:?} for {}", arg_type, location),
+            GenError::IdentCollision { location, ident } => write!(f, "identifier `{}` generated for {} is already in use", ident, location),
+            GenError::Config { message } => write!(f, "invalid generator config: {}", message),
+            GenError::Syntax { module, source } => write!(f, "generated code for module {} is not valid rust: {}", module, source),
+        }
+    }
+}
+
+impl Error for GenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GenError::Io { source, .. } => Some(source),
+            GenError::Json { source, .. } => Some(source),
+            GenError::Syntax { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -1,134 +1,675 @@
-use std::{collections::{hash_map, HashMap, HashSet}, fs::File, hash, io::prelude::*, ops::RangeBounds, option, path::PathBuf};
+mod compat;
+mod config;
+mod decode;
+mod describe;
+mod error;
+mod tags;
+mod values;
+
+use std::{collections::{BTreeMap, BTreeSet}, fs::{self, File}, io::prelude::*, path::PathBuf};
 use proc_macro2::{token_stream, Ident};
 use quote::{format_ident, quote};
 use prettier_please;
-use serde_json::Value;
-use crate::raw_types::{Action, ActionArgOptions, ActionIconOptions};
+use crate::raw_types::{Action, ActionArgOptions, ActionDump, ActionIcon, ActionIconReturnTypeOption, ActionTags, CodeBlock, LoadMode};
+use tags::TagSet;
+
+pub use compat::{api_changes, ApiChange, ApiChangeKind, CompatReport};
+pub use config::{GenConfig, OutputLayout, Rank, Section};
+pub use describe::describe_action;
+pub use error::{GenError, Location};
+
+/// Summary of a successful [`gen_types`] or [`gen_with_config`] run.
+#[derive(Debug, Default)]
+pub struct GenReport {
+    /// Every module that was written, in the order they were written.
+    pub modules: Vec<ModuleReport>,
+}
+
+/// A single generated module.
+#[derive(Debug)]
+pub struct ModuleReport {
+    pub name: String,
+    pub path: PathBuf,
+    /// Number of action variants in the module.
+    pub actions: usize,
+}
 
 /// Generates all enum variants for the given action dump file in the directory specified by mod_path.
-pub fn gen_types<T: Into<PathBuf>>(action_dump_path: T, module_path: T) -> () {
+/// This uses the default [`GenConfig`], see [`gen_with_config`] to change it.
+pub fn gen_types<T: Into<PathBuf>>(action_dump_path: T, module_path: T) -> Result<GenReport, GenError> {
+    gen_with_config(action_dump_path, module_path, &GenConfig::default())
+}
+
+/// Generates the sections of the given action dump file enabled in `config`, in the directory specified by mod_path.
+pub fn gen_with_config<T: Into<PathBuf>>(action_dump_path: T, module_path: T, config: &GenConfig) -> Result<GenReport, GenError> {
     let action_dump_path = action_dump_path.into();
-    let mut file = File::open(action_dump_path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-
-    let json = serde_json::from_str::<serde_json::Value>(&contents).unwrap();
-    let actions = json.as_object().expect("Your action dump file is not a json object!")
-        .get("actions").expect("Your action dump file has no actions!")
-        .as_array().expect("Your action dump file's actions entry is not an array!");
-
-    let mut module_code = hash_map::HashMap::new();
-    module_code.insert("player_event".to_string(), Vec::new());
-    module_code.insert("player_action".to_string(), Vec::new());
-    module_code.insert("if_player".to_string(), Vec::new());
-    module_code.insert("entity_event".to_string(), Vec::new());
-    module_code.insert("entity_action".to_string(), Vec::new());
-    module_code.insert("if_entity".to_string(), Vec::new());
-    module_code.insert("game_action".to_string(), Vec::new());
-    module_code.insert("if_game".to_string(), Vec::new());
-    module_code.insert("set_variable".to_string(), Vec::new());
-    module_code.insert("if_variable".to_string(), Vec::new());
-    module_code.insert("repeat".to_string(), Vec::new());
-    module_code.insert("control".to_string(), Vec::new());
-    module_code.insert("select_object".to_string(), Vec::new());
-
-    let mut enum_names = HashSet::new();
-    let mut tags = TagSet::new();
+    let contents = fs::read_to_string(&action_dump_path)
+        .map_err(|source| GenError::Io { path: action_dump_path.clone(), source })?;
 
-    for (i, action) in actions.iter().enumerate() {
-        // Skips call function and call process actions.
-        // TODO: See if there's a way to parse these.
-        if action.as_object().unwrap().get("name").unwrap().as_str().unwrap() == "dynamic" {
-            continue;
+    let generated = generate_from_str(&contents, config)?;
+
+    Ok(GenReport {
+        modules: write_modules(module_path.into(), generated, config)?,
+    })
+}
+
+/// The code generated for a single module, before it's formatted.
+#[derive(Debug, Clone)]
+pub struct GeneratedModule {
+    pub name: String,
+    pub code: token_stream::TokenStream,
+    /// Number of action variants in the module.
+    pub actions: usize,
+}
+
+impl GeneratedModule {
+    /// The formatted source code of the module.
+    pub fn source(&self) -> Result<String, GenError> {
+        format_module(&self.name, self.code.clone())
+    }
+}
+
+/// Everything generated from an action dump, see [`generate`].
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedCode {
+    /// Every generated module, action modules first in the order their codeblocks are listed.
+    pub modules: Vec<GeneratedModule>,
+}
+
+impl GeneratedCode {
+    /// The code of every module, keyed by module name.
+    pub fn tokens(&self) -> BTreeMap<String, token_stream::TokenStream> {
+        self.modules.iter().map(|module| (module.name.clone(), module.code.clone())).collect()
+    }
+
+    /// The formatted source code of every module, keyed by module name.
+    pub fn sources(&self) -> Result<BTreeMap<String, String>, GenError> {
+        self.modules.iter().map(|module| Ok((module.name.clone(), module.source()?))).collect()
+    }
+
+    /// Every module declared inline, ie. `pub mod player_action { ... }`.
+    pub fn inline(&self) -> token_stream::TokenStream {
+        let modules = self.modules.iter().map(|module| {
+            let name = format_ident!("{}", module.name);
+            let code = &module.code;
+            quote!(pub mod #name { #code })
+        });
+        quote!(#(#modules)*)
+    }
+
+    /// A declaration for every module, ie. `pub mod player_action;`.
+    pub fn declarations(&self) -> token_stream::TokenStream {
+        let names = self.modules.iter().map(|module| format_ident!("{}", module.name));
+        quote!(#(pub mod #names;)*)
+    }
+}
+
+/// Generates the sections of the action dump enabled in `config` without writing anything to disk.
+pub fn generate_from_str(contents: &str, config: &GenConfig) -> Result<GeneratedCode, GenError> {
+    generate(&load_dump(contents, config.load_mode)?, config)
+}
+
+/// Generates the sections of an already deserialized action dump enabled in `config` without writing anything to disk.
+pub fn generate(dump: &ActionDump, config: &GenConfig) -> Result<GeneratedCode, GenError> {
+    let imports = config.imports.iter()
+        .map(|import| parse_config::<syn::UseTree>(import, "import"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let derives = config.derives.iter()
+        .map(|derive| parse_config::<syn::Path>(derive, "derive"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut generated = Vec::new();
+    let mut push = |default_name: &str, code, actions| -> Result<(), GenError> {
+        let name = module_ident(config, default_name)?.to_string();
+        generated.push(GeneratedModule { name, code, actions });
+        Ok(())
+    };
+
+    if config.is_enabled(Section::Actions) {
+        for (name, code, actions) in gen_actions(dump, config, &imports, &derives)? {
+            push(&name, code, actions)?;
         }
-        let action = serde_json::from_value::<Action>(action.clone()).expect(&format!("Failed to parse action #{}!", i));
-        let action_block = action.codeblock_name.to_ascii_lowercase().replace(" ", "_");
-        let action_data = gen_action(action, &mut enum_names, &mut tags);
+    }
+    if config.is_enabled(Section::BlockKind) {
+        push("block_kind", gen_block_kind(&dump.codeblocks)?, 0)?;
+    }
+    if config.is_enabled(Section::GameValues) {
+        push("game_value", values::gen_game_values(&dump.game_value_categories, &dump.game_values)?, 0)?;
+    }
+    if config.is_enabled(Section::Particles) {
+        push("particle", values::gen_particles(&dump.particle_categories, &dump.particles)?, 0)?;
+    }
+    if config.is_enabled(Section::Sounds) {
+        push("sound", values::gen_sounds(&dump.sound_categories, &dump.sounds)?, 0)?;
+    }
+    if config.is_enabled(Section::Potions) {
+        push("potion", values::gen_potions(&dump.potions)?, 0)?;
+    }
+
+    Ok(GeneratedCode { modules: generated })
+}
 
-        module_code.get_mut(&action_block).unwrap().push(action_data);
+/// Generates a module for every block with actions, along with the decode, requirements, returns and tags modules they use.
+/// Modules are returned by their default name.
+fn gen_actions(dump: &ActionDump, config: &GenConfig, imports: &[token_stream::TokenStream], derives: &[token_stream::TokenStream]) -> Result<Vec<(String, token_stream::TokenStream, usize)>, GenError> {
+    let mut generated = Vec::new();
+    let modules = group_actions(dump, config)?;
+
+    // The support module the generated from_json functions use.
+    let decode_module = module_ident(config, "decode")?;
+    generated.push(("decode".to_string(), decode::gen_decode_module(), 0));
+
+    // Rank and Requirements, which every block's requirements function returns.
+    let requirements_module = module_ident(config, "requirements")?;
+    generated.push(("requirements".to_string(), gen_requirements_module(), 0));
+
+    // ReturnValue and the Output marker for the variables actions set.
+    let returns_module = module_ident(config, "returns")?;
+    generated.push(("returns".to_string(), gen_returns_module(&decode_module), 0));
+
+    // Tags are generated once in their own module, shared by every action with an identical tag.
+    let tags_module = module_ident(config, "tags")?;
+    let mut tags = TagSet::new();
+    for (_, codeblock, actions) in &modules {
+        let block_name = snake_to_camel_case(&module_name(&codeblock.name));
+        for ((action, location), action_name) in actions {
+            for (tag_index, tag) in action.tags.iter().enumerate() {
+                tags.insert(tag, &block_name, action_name, location.join(&format!("tags[{}]", tag_index)));
+            }
+        }
     }
+    tags.resolve()?;
+    generated.push(("tags".to_string(), tags.gen_module(derives, &decode_module)?, 0));
+
+    for (i, codeblock, actions) in modules {
+        let module_name = module_name(&codeblock.name);
+        let enum_name = ident(&snake_to_camel_case(&module_name), || Location::root(&format!("codeblocks[{}].name", i)))?;
+
+        let mut action_defs = Vec::new();
+        let mut compile_functions = Vec::new();
+        let mut decoders = Vec::new();
+        let mut requirements = Vec::new();
+        let mut returns = Vec::new();
+        let mut has_output = false;
+        let mut tag_types = BTreeSet::new();
+        let mut variants = Vec::new();
+        // Looked up in order, so an action's own name wins over another's display name or alias.
+        let mut names = [Vec::new(), Vec::new(), Vec::new()];
+        for ((action, location), action_name) in actions {
+            let (rank, tokens, rank_and_tokens, advanced) = action_requirements(action, &location)?;
+            let rank = format_ident!("{}", format!("{:?}", rank));
+            let variant = ident(&action_name, || location.join("name"))?;
+            let display_name = strip_colour(&action.icon.name).trim().to_string();
+            names[0].push((action.name.clone(), variant.clone()));
+            if !display_name.is_empty() {
+                names[1].push((display_name, variant.clone()));
+            }
+            names[2].extend(action.aliases.iter().map(|alias| (alias.clone(), variant.clone())));
+            variants.push((variant.clone(), action.name.clone()));
+            requirements.push(quote!(
+                #enum_name::#variant { .. } => Requirements { rank: Rank::#rank, tokens: #tokens, rank_and_tokens: #rank_and_tokens, advanced: #advanced }
+            ));
 
-    let mut module_path: PathBuf = module_path.into();
-    for (module_name, module_code) in module_code {
-        module_path.push(format!("{}.rs", module_name));
-        eprintln!("Writing module {} to {}...", module_name, module_path.to_str().unwrap());
-        let mut file = File::create(&module_path).unwrap();
+            let action_code = if action.is_dynamic() {
+                gen_dynamic_action(action, codeblock, &action_name, &location, &tags)?
+            }
+            else {
+                gen_action(action, codeblock, &action_name, &location, &tags)?
+            };
+            action_defs.push(action_code.variant);
+            compile_functions.push(action_code.compile);
+            decoders.push(action_code.decode);
+            returns.push(action_code.returns);
+            has_output |= action_code.has_output;
+            tag_types.extend(action_code.tags);
+        }
 
-        let enum_name = quote::format_ident!("{}", snake_to_camel_case(&module_name));
-        let (action_defs, (compile_functions, tag_defs)): (Vec<_>, (Vec<_>, Vec<_>)) = module_code.into_iter().unzip();
+        let action_count = action_defs.len();
+        let block_identifier = &codeblock.identifier;
+        let derive = if derives.is_empty() { quote!() } else { quote!(#[derive(#(#derives),*)]) };
+        let returns_import = if has_output { quote!(use super::#returns_module::{Output, ReturnValue};) } else { quote!(use super::#returns_module::ReturnValue;) };
+        let tag_import = if tag_types.is_empty() { quote!() } else { quote!(use super::#tags_module::{#(#tag_types),*};) };
+        let name_enum = format_ident!("{}Name", enum_name);
+        let (name_strings, name_variants): (Vec<_>, Vec<_>) = names.into_iter().flatten().unzip();
+        let (variants, action_names): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
         let module_code = quote!(
             use either::Either;
             use serde_json::Value;
-            use crate::types::*;
-            use crate::block::block_types::subactions::*;
+            use super::#decode_module::{block_action, ArgReader, DecodeError};
+            use super::#requirements_module::{Rank, Requirements};
+            #returns_import
+            #tag_import
+            #(use #imports;)*
 
+            #derive
             pub enum #enum_name {
                 #(#action_defs),*
             }
 
+            #[allow(deprecated)]
             impl #enum_name {
                 pub fn compile(&self) -> Value {
                     match self {
                         #(#compile_functions)*
                     }
                 }
+
+                /// The rank and tokens needed to use the block's action.
+                pub fn requirements(&self) -> Requirements {
+                    match self {
+                        #(#requirements),*
+                    }
+                }
+
+                /// The types of value the block's action sets its output variable to, empty if it doesn't set one.
+                pub fn return_types(&self) -> &'static [ReturnValue] {
+                    match self {
+                        #(#returns),*
+                    }
+                }
+
+                /// Reads a block back from the json `compile` produces for it.
+                pub fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                    match block_action(value, #block_identifier)? {
+                        #(#decoders)*
+                        action => Err(DecodeError::UnknownAction { block: #block_identifier, action: action.to_string() }),
+                    }
+                }
+
+                /// The block's action, without its arguments.
+                pub fn action(&self) -> #name_enum {
+                    match self {
+                        #(#enum_name::#variants { .. } => #name_enum::#variants),*
+                    }
+                }
             }
 
-            #(#tag_defs)*
+            /// The actions of the block without their arguments, for looking them up by name.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum #name_enum {
+                #(#variants),*
+            }
+
+            impl #name_enum {
+                /// Looks an action up by its name in the dump, the name on its icon or any of its aliases, ignoring case.
+                pub fn from_name(name: &str) -> Option<Self> {
+                    [#((#name_strings, #name_enum::#name_variants)),*].into_iter()
+                        .find(|(other, _)| other.eq_ignore_ascii_case(name))
+                        .map(|(_, action)| action)
+                }
+
+                /// The action's name in the dump, as used in template json.
+                pub fn name(&self) -> &'static str {
+                    match self {
+                        #(#name_enum::#variants => #action_names),*
+                    }
+                }
+            }
         );
 
-        let module_code = syn::parse2(module_code).unwrap();
-        let module_code = prettier_please::unparse(&module_code);
+        generated.push((module_name, module_code, action_count));
+    }
+    Ok(generated)
+}
+
+/// A block enum's actions along with their variant names.
+type BlockActions<'a> = Vec<((&'a Action, Location), String)>;
+
+/// Groups the actions `config` keeps by codeblock and names their variants, along with each codeblock's index in the dump.
+/// Blocks without actions, such as else, function and process, are left out since they have nothing to generate.
+fn group_actions<'a>(dump: &'a ActionDump, config: &GenConfig) -> Result<Vec<(usize, &'a CodeBlock, BlockActions<'a>)>, GenError> {
+    // Every codeblock gets a module, in the order the codeblocks are listed.
+    let mut modules = dump.codeblocks.iter().map(|codeblock| (codeblock, Vec::new())).collect::<Vec<_>>();
+    for (i, action) in dump.actions.iter().enumerate() {
+        let location = Location::action(i, &action.name);
+        let (_, module) = modules.iter_mut().find(|(codeblock, _)| codeblock.name == action.codeblock_name).ok_or_else(|| GenError::UnknownCodeblock {
+            location: location.join("codeblockName"),
+            codeblock: action.codeblock_name.clone(),
+        })?;
+        if let Some((icon_count, count)) = action.tag_count_mismatch() {
+            return Err(schema_error(location.join("icon.tags"), &format!("the icon lists {} tags but the action has {}", icon_count, count)));
+        }
+        if config.max_rank.is_some_and(|max_rank| action_requirements(action, &location).is_ok_and(|(rank, ..)| rank > max_rank)) {
+            continue;
+        }
+        module.push((action, location));
+    }
+
+    modules.into_iter()
+        .enumerate()
+        .filter(|(_, (_, actions))| !actions.is_empty())
+        .map(|(i, (codeblock, actions))| {
+            let names = action_names(&actions)?;
+            Ok((i, codeblock, actions.into_iter().zip(names).collect()))
+        })
+        .collect()
+}
+
+/// Writes the generated modules to the `module_path` directory, laid out as `config` says.
+fn write_modules(mut module_path: PathBuf, generated: GeneratedCode, config: &GenConfig) -> Result<Vec<ModuleReport>, GenError> {
+    let mut reports = Vec::new();
+    match &config.layout {
+        OutputLayout::Files | OutputLayout::FilesWithMod => {
+            for module in &generated.modules {
+                module_path.push(format!("{}.rs", module.name));
+                write_module(&module_path, &module.name, module.code.clone())?;
+                reports.push(ModuleReport { name: module.name.clone(), path: module_path.clone(), actions: module.actions });
+                module_path.pop();
+            }
+
+            if config.layout == OutputLayout::FilesWithMod {
+                module_path.push("mod.rs");
+                write_module(&module_path, "mod", generated.declarations())?;
+            }
+        }
+        OutputLayout::SingleFile(file_name) => {
+            module_path.push(file_name);
+            for module in &generated.modules {
+                reports.push(ModuleReport { name: module.name.clone(), path: module_path.clone(), actions: module.actions });
+            }
+            write_module(&module_path, file_name, generated.inline())?;
+        }
+    }
+    Ok(reports)
+}
+
+/// Formats `module_code` and writes it to `path`.
+fn write_module(path: &PathBuf, module_name: &str, module_code: token_stream::TokenStream) -> Result<(), GenError> {
+    eprintln!("Writing module {} to {}...", module_name, path.to_str().unwrap_or_default());
 
-        file.write_all(module_code.to_string().as_bytes()).unwrap();
+    let module_code = format_module(module_name, module_code)?;
+    File::create(path)
+        .and_then(|mut file| file.write_all(module_code.as_bytes()))
+        .map_err(|source| GenError::Io { path: path.clone(), source })
+}
+
+/// Formats the code of a module with prettier-please.
+fn format_module(module_name: &str, module_code: token_stream::TokenStream) -> Result<String, GenError> {
+    let module_code = syn::parse2(module_code)
+        .map_err(|source| GenError::Syntax { module: module_name.to_string(), source })?;
+    Ok(prettier_please::unparse(&module_code))
+}
 
-        module_path.pop();
+/// Generates the `BlockKind` enum, which has a variant for every codeblock in the dump.
+fn gen_block_kind(codeblocks: &[CodeBlock]) -> Result<token_stream::TokenStream, GenError> {
+    let mut variants = Vec::new();
+    let mut names = Vec::new();
+    let mut identifiers = Vec::new();
+    for (i, codeblock) in codeblocks.iter().enumerate() {
+        let variant = ident(&snake_to_camel_case(&module_name(&codeblock.name)), || Location::root(&format!("codeblocks[{}].name", i)))?;
+        if variants.contains(&variant) {
+            return Err(GenError::IdentCollision { location: Location::root(&format!("codeblocks[{}].name", i)), ident: variant.to_string() });
+        }
+        variants.push(variant);
+        names.push(codeblock.name.clone());
+        identifiers.push(codeblock.identifier.clone());
     }
+
+    Ok(quote!(
+        /// Every kind of codeblock.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum BlockKind {
+            #(#variants),*
+        }
+
+        impl BlockKind {
+            /// The identifier used for the block in template json, eg. `player_action`.
+            pub fn identifier(&self) -> &'static str {
+                match *self {
+                    #(BlockKind::#variants => #identifiers),*
+                }
+            }
+
+            /// The name of the block, eg. `PLAYER ACTION`.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(BlockKind::#variants => #names),*
+                }
+            }
+
+            /// Looks up a block by its template json identifier.
+            pub fn from_identifier(identifier: &str) -> Option<Self> {
+                match identifier {
+                    #(#identifiers => Some(BlockKind::#variants),)*
+                    _ => None,
+                }
+            }
+        }
+    ))
+}
+
+/// Generates the `requirements` module, with the `Rank` and `Requirements` types.
+fn gen_requirements_module() -> token_stream::TokenStream {
+    let ranks = Rank::ALL.map(|rank| format_ident!("{}", format!("{:?}", rank)));
+
+    quote!(
+        /// The ranks DiamondFire locks actions behind, lowest first.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub enum Rank {
+            #[default]
+            #(#ranks),*
+        }
+
+        /// What a player needs to use an action.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct Requirements {
+            /// The lowest rank that can use the action.
+            pub rank: Rank,
+            /// Whether the action can be unlocked with tokens.
+            pub tokens: bool,
+            /// Whether the action needs both the rank and tokens.
+            pub rank_and_tokens: bool,
+            /// Whether the action is only shown in advanced mode.
+            pub advanced: bool,
+        }
+
+        impl Requirements {
+            /// Whether `rank` is high enough for the action, tokens aside.
+            pub fn allows(&self, rank: Rank) -> bool {
+                rank >= self.rank
+            }
+        }
+    )
+}
+
+/// Generates the `returns` module, with the `ReturnValue` type and the `Output` marker.
+fn gen_returns_module(decode_module: &Ident) -> token_stream::TokenStream {
+    quote!(
+        use std::{fmt, marker::PhantomData, ops::{Deref, DerefMut}};
+        use serde_json::Value;
+        use super::#decode_module::{DecodeError, FromItem};
+
+        /// A type of value an action can set its output variable to.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct ReturnValue {
+            /// The name of the value's Rust type, eg. `Number`.
+            pub type_name: &'static str,
+            pub description: &'static str,
+        }
+
+        /// The variable an action sets, `R` being the type of value it's set to.
+        /// Derefs to the variable itself.
+        pub struct Output<V, R> {
+            variable: V,
+            returns: PhantomData<fn() -> R>,
+        }
+
+        impl<V, R> Output<V, R> {
+            pub fn new(variable: V) -> Self {
+                Self { variable, returns: PhantomData }
+            }
+
+            pub fn into_inner(self) -> V {
+                self.variable
+            }
+        }
+
+        impl<V, R> From<V> for Output<V, R> {
+            fn from(variable: V) -> Self {
+                Self::new(variable)
+            }
+        }
+
+        impl<V, R> Deref for Output<V, R> {
+            type Target = V;
+
+            fn deref(&self) -> &V {
+                &self.variable
+            }
+        }
+
+        impl<V, R> DerefMut for Output<V, R> {
+            fn deref_mut(&mut self) -> &mut V {
+                &mut self.variable
+            }
+        }
+
+        impl<V: Clone, R> Clone for Output<V, R> {
+            fn clone(&self) -> Self {
+                Self::new(self.variable.clone())
+            }
+        }
+
+        impl<V: fmt::Debug, R> fmt::Debug for Output<V, R> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple("Output").field(&self.variable).finish()
+            }
+        }
+
+        impl<V: PartialEq, R> PartialEq for Output<V, R> {
+            fn eq(&self, other: &Self) -> bool {
+                self.variable == other.variable
+            }
+        }
+
+        impl<V: FromItem, R> FromItem for Output<V, R> {
+            fn from_item(item: &Value) -> Result<Self, DecodeError> {
+                V::from_item(item).map(Self::new)
+            }
+        }
+    )
+}
+
+/// Deserializes an action dump, parsing each action on its own so errors can point at the offending one.
+fn load_dump(contents: &str, mode: LoadMode) -> Result<ActionDump, GenError> {
+    let mut json = serde_json::from_str::<serde_json::Value>(contents)
+        .map_err(|source| GenError::Json { location: Location::root("$"), source })?;
+    let actions = json.as_object_mut()
+        .ok_or_else(|| schema_error(Location::root("$"), "the action dump is not a json object"))?
+        .get_mut("actions")
+        .ok_or_else(|| schema_error(Location::root("actions"), "the action dump has no actions"))?
+        .as_array_mut()
+        .ok_or_else(|| schema_error(Location::root("actions"), "the action dump's actions entry is not an array"))?;
+    let actions = std::mem::take(actions);
+
+    let mut dump = mode.from_value::<ActionDump>(json)
+        .map_err(|source| GenError::Json { location: Location::root("$"), source })?;
+
+    for (i, action) in actions.into_iter().enumerate() {
+        let name = action.get("name").and_then(|name| name.as_str()).unwrap_or_default();
+        let location = Location::action(i, name);
+        let action = mode.from_value::<Action>(action)
+            .map_err(|source| GenError::Json { location: location.clone(), source })?;
+        dump.actions.push(action);
+    }
+
+    Ok(dump)
+}
+
+/// Picks the variant name of every action in a block.
+/// Actions are named after their icon, falling back to their name if the icon has none or another action has the same one.
+/// Collisions are settled by the action names rather than the order of the dump, so reordering the dump never renames a variant.
+fn action_names(actions: &[(&Action, Location)]) -> Result<Vec<String>, GenError> {
+    let preferred = actions.iter().map(|(action, _)| {
+        let name = if action.is_dynamic() {
+            snake_to_camel_case(&module_name(&action.codeblock_name))
+        }
+        else {
+            format_name(&action.icon.name)
+        };
+        if name.is_empty() { format_name(&action.name) } else { name }
+    }).collect::<Vec<_>>();
+
+    // Of the actions sharing an icon name, one already named that way keeps it, otherwise the one that sorts first by name does.
+    // The rest fall back to their own name.
+    let priority = |i: usize| (format_name(&actions[i].0.name) != preferred[i], &actions[i].0.name);
+    let mut names = preferred.clone();
+    for (i, (action, _)) in actions.iter().enumerate() {
+        let keeps_name = (0..actions.len())
+            .all(|other| preferred[other] != preferred[i] || priority(other) >= priority(i));
+        if !keeps_name {
+            names[i] = format_name(&action.name);
+        }
+    }
+
+    // Any names that still collide are suffixed with N, again in order of action name.
+    let mut order = (0..actions.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| actions[*a].0.name.cmp(&actions[*b].0.name));
+    let mut used_names = BTreeSet::new();
+    for i in order {
+        if used_names.contains(&names[i]) {
+            names[i] = format!("{}N", names[i]);
+        }
+        if !used_names.insert(names[i].clone()) {
+            return Err(GenError::IdentCollision { location: actions[i].1.join("name"), ident: names[i].clone() });
+        }
+    }
+
+    Ok(names)
+}
+
+/// The code generated for a single action, spread across its block's enum and impl.
+struct ActionCode {
+    /// The action's variant of the block enum.
+    variant: token_stream::TokenStream,
+    /// The action's arm of `compile`.
+    compile: token_stream::TokenStream,
+    /// The action's arm of `from_json`.
+    decode: token_stream::TokenStream,
+    /// The action's arm of `return_types`.
+    returns: token_stream::TokenStream,
+    /// Whether one of the action's fields is marked as an `Output`.
+    has_output: bool,
+    /// The tag enums the action's fields use.
+    tags: Vec<Ident>,
 }
 
 /// Generates a single enum variant for a given action object.
-fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSet) -> (token_stream::TokenStream, (token_stream::TokenStream, token_stream::TokenStream)) {
-    let mut action_name = match &action.icon {
-        ActionIconOptions::Icon(icon) => format_name(&icon.name),
-        ActionIconOptions::Event(argless) => format_name(&argless.name)
-    };
+fn gen_action(action: &Action, codeblock: &CodeBlock, action_name: &str, location: &Location, tags: &TagSet) -> Result<ActionCode, GenError> {
     let unformated_action_name = action.name.clone();
-    // if action.aliases.len() > 0 {
-    //     action_name = format_name(&action.aliases[0]);
-    // }
-    if action_name == "" || used_names.contains(&format!("{} {}", action_name, action.codeblock_name)) {
-        action_name = format_name(&action.name);
-    }
-    if used_names.contains(&format!("{} {}", action_name, action.codeblock_name)) {
-        action_name = format!("{}N", action_name)
-    }
-    // if used_names.contains(&action_name) &&
-    //     match &action.icon {
-    //         ActionIconOptions::Icon(icon) => icon.,
-    //         ActionIconOptions::Argless(argless) => format_name(&argless.name)
-    //     }
-    // {
-    //     action_name = format_name(&action.[0]);
-    // }
-    used_names.insert(format!("{} {}", action_name, action.codeblock_name));
-
-    let action_name = quote::format_ident!("{}", action_name);
+    let action_name = ident(action_name, || location.join("icon.name"))?;
 
     let mut arg_types = Vec::new();
-    let args = match action.icon {
-        ActionIconOptions::Icon(icon) => icon.arguments,
-        ActionIconOptions::Event(_) => Vec::new(),
-    };
+    let (args, return_values) = (action.icon.arguments(), action.icon.return_values());
 
     let mut arg_names = Vec::new();
+    let mut arg_readers = Vec::new();
+
+    // The types of value the action sets its output variable to, the "OR"s between them are skipped.
+    let mut return_types = Vec::new();
+    let mut return_type_names = Vec::new();
+    let mut return_descriptions = Vec::new();
+    for (return_index, return_value) in return_values.iter().enumerate() {
+        if let ActionIconReturnTypeOption::Arg(return_value) = return_value {
+            let return_type = arg_type_to_rust(&return_value.type_name)
+                .ok_or_else(|| unknown_arg_type(location.join(&format!("icon.returnValues[{}].type", return_index)), &return_value.type_name))?;
+            return_type_names.push(return_type.to_string());
+            return_types.push(return_type);
+            return_descriptions.push(strip_colour(&return_value.description.join(" ")));
+        }
+    }
+    let mut output_type = return_types.split_first()
+        .map(|(first, rest)| rest.iter().fold(first.clone(), |output, return_type| quote!(Either<#output, #return_type>)));
 
     // Turns the argument list into a list of fields with types.
     let mut i = 0;
     let len = args.len();
     while i < len {
         if let ActionArgOptions::Arg(arg ) = &args[i] {
+            let arg_location = location.join(&format!("icon.arguments[{}]", i));
             let mut outer_arg = arg.clone();
-            let mut output = arg_type_to_rust(&outer_arg.arg_type);
+            let mut alternatives = Vec::new();
+            let mut output = arg_type_to_rust(&outer_arg.arg_type)
+                .ok_or_else(|| unknown_arg_type(arg_location.join("type"), &outer_arg.arg_type))?;
             loop {
                 if len > i+2 {
                     if let ActionArgOptions::Text{ text } = &args[i+1] {
@@ -138,8 +679,10 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
                                     outer_arg.optional = true;
                                 }
                                 else {
-                                    let inner_arg_type = arg_type_to_rust(&arg.arg_type);
+                                    let inner_arg_type = arg_type_to_rust(&arg.arg_type)
+                                        .ok_or_else(|| unknown_arg_type(location.join(&format!("icon.arguments[{}].type", i+2)), &arg.arg_type))?;
                                     output = quote!(Either<#output, #inner_arg_type>);
+                                    alternatives.push(arg.description.join(" "));
                                 }
                                 i += 2;
                                 continue;
@@ -150,108 +693,66 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
                 break;
             }
 
+            // The first plain variable is the one the action sets, it's marked with the type it's set to.
+            if outer_arg.arg_type == "VARIABLE" && alternatives.is_empty() && !outer_arg.plural && !outer_arg.optional {
+                if let Some(returns) = output_type.take() {
+                    output = quote!(Output<#output, #returns>);
+                }
+            }
+
             if outer_arg.plural {
                 output = quote!(Vec<#output>);
+                arg_readers.push(quote!(reader.plural()?));
             }
             else if outer_arg.optional {
                 output = quote!(Option<#output>);
+                arg_readers.push(quote!(reader.optional()?));
+            }
+            else {
+                arg_readers.push(quote!(reader.required()?));
             }
 
-            let arg_name = quote::format_ident!("{}", remove_leading_nonalpha(&outer_arg.description[0]).replace(" ", "_").replace(|c: char| {!c.is_ascii_alphanumeric() && c != '_'}, "").to_lowercase().replace("type", "type_"));
+            let description = outer_arg.description.first()
+                .ok_or_else(|| schema_error(arg_location.join("description"), "argument has no description to name it after"))?;
+            let arg_name = ident(&field_name(description), || arg_location.join("description"))?;
+            if arg_names.contains(&arg_name) {
+                return Err(GenError::IdentCollision { location: arg_location.join("description"), ident: arg_name.to_string() });
+            }
             arg_names.push(arg_name.clone());
+            let arg_docs = doc_attrs(&arg_docs(&outer_arg.description, &alternatives, &outer_arg.notes));
             arg_types.push(quote!(
+                #arg_docs
                 #arg_name: #output
             ));
         }
-        eprintln!("arg: {:?}", args[i]);
         i += 1;
     }
-    eprintln!("args: {:?}", arg_types);
 
-    // turns the tag list into a list of fields with types and a tokenstream of tag enum definitions.
-    let mut tag_types = Vec::new();
-    let mut tag_names = Vec::new();
-    let mut tag_defs: Vec<token_stream::TokenStream> = Vec::new();
-    for tag in action.tags {
-        let tag_type = format_ident!("{}{}", &format_name(&tag.name), action_name);
-        let tag_ident = format_ident!("{}_tag", remove_leading_nonalpha(&tag.name).replace(" ", "_").replace(|c: char| {!c.is_ascii_alphanumeric() && c != '_'}, "").to_lowercase().replace("type", "type_"));
-        let tag_name_unformated = tag.name.clone();
-        tag_types.push(quote!(#tag_ident: #tag_type));
-        tag_names.push(tag_ident.clone());
-
-        let mut tag_default = format_ident!("placeholder");
-        let mut tag_options = Vec::new();
-        let mut tag_strings = Vec::new();
-        for option in tag.options {
-            let option_name = if format_name(&replace_numeric(&option.name)) != "" {
-                format_ident!("{}", &format_name(&replace_numeric(&option.name)))
-            } 
-            else if option.aliases.len() > 0 && format_name(&remove_leading_nonalpha(&option.aliases[0])) != "" {
-                format_ident!("{}", &format_name(&replace_numeric(&option.aliases[0])))
-            }
-            else {
-                format_ident!("{}", snake_to_camel_case(&format_name(&option.icon.description[0])))
-                
-            };
-
-            if option.name == tag.default_option {
-                tag_default = option_name.clone();
-            }
-
-            tag_options.push(option_name.clone());
-            tag_strings.push(option.name.clone());
-        }
-
-        let tag_def = quote!(
-            #[derive(Debug, Clone)]
-            pub enum #tag_type {
-                #(#tag_options),*
-            }
-
-            impl #tag_type {
-                pub fn json(&self) -> serde_json::Map<String, Value> {
-                    let mut map = serde_json::Map::new();
-                    let mut data = serde_json::Map::new();
-                    data.insert("option".to_string(),
-                    match self {
-                        #(#tag_type::#tag_options => Value::String(#tag_strings.to_string())),*
-                    });
-                    data.insert("tag".to_string(), Value::String(#tag_name_unformated.to_string()));
-                    data.insert("action".to_string(), Value::String(#unformated_action_name.to_string()));
-                    data.insert("block".to_string(), Value::String(#unformated_action_name.to_string()));
-
-                    map.insert("data".to_string(), Value::Object(data));
-                    map.insert("id".to_string(), Value::String("bl_tag".to_string()));
-                    map
-                }
-            }
-
-            impl Default for #tag_type {
-                fn default() -> Self {
-                    Self::#tag_default
-                }
-            }
-        );
-
-        tag_defs.push(tag_def);
-    }
-    let tag_defs = quote!(#(#tag_defs)*);
+    let TagCode { fields: tag_types, names: tag_names, readers: tag_readers, types: tag_enums } = gen_tags(&action.tags, tags, location, &arg_names)?;
 
     // Turns the subaction list into a field, if it exists.
-    let subactions = if action.sub_action_blocks == vec!["if_entity", "if_var", "if_game"] {
-        quote!(subaction: SelectEntity,)
+    let subaction_type = if action.sub_action_blocks == vec!["if_entity", "if_var", "if_game"] {
+        Some(quote!(SelectEntity))
     }
     else if action.sub_action_blocks == vec!["if_player", "if_var", "if_game"] {
-        quote!(subaction: SelectPlayer,)
+        Some(quote!(SelectPlayer))
     }
     else if action.sub_action_blocks == vec!["if_player", "if_entity", "if_var", "if_game"] {
-        quote!(subaction: AllSubactions,)
+        Some(quote!(AllSubactions))
     }
     else {
-        quote!()
+        None
+    };
+    let subactions = match &subaction_type {
+        Some(subaction_type) => quote!(subaction: #subaction_type,),
+        None => quote!(),
     };
 
+    let docs = doc_attrs(&action_docs(&action.icon));
+    let deprecated = deprecated_attr(action_deprecated_note(&action.icon));
     let enum_var = quote!(
+        #docs
+        #deprecated
         #action_name {
             #subactions
             #(#arg_types,)*
@@ -259,7 +760,7 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
         }
     );
 
-    let subactions = if action.sub_action_blocks.len() > 0 {
+    let subactions = if !action.sub_action_blocks.is_empty() {
         quote!(subaction,)
     }
     else {
@@ -267,13 +768,15 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
     };
 
     // creates the compile function for the action.
-    let block_name = quote::format_ident!("{}", snake_to_camel_case(&action.codeblock_name.to_ascii_lowercase().replace(" ", "_")));
-    
-    let subaction_compiler = if action.sub_action_blocks.len() > 0 {
+    let block_name = quote::format_ident!("{}", snake_to_camel_case(&module_name(&codeblock.name)));
+    let block_identifier = &codeblock.identifier;
+
+    let subaction_compiler = if !action.sub_action_blocks.is_empty() {
         quote!(
             let mut subaction = subaction.compile();
             let value = subaction.as_object_mut().unwrap();
             value.insert("subaction".to_string(), value["action"].clone());
+            value.insert("block".to_string(), serde_json::Value::String(#block_identifier.to_string()));
             value.insert("action".to_string(), serde_json::Value::String(#unformated_action_name.to_string()));
             subaction
         )
@@ -285,11 +788,12 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
     let compile_function = quote!(
         #block_name::#action_name {#subactions #(#arg_names,)* #(#tag_names),*} => {
             let mut map = serde_json::Map::new();
-            let item_args = compile(vec![#(#arg_names.json()),*], vec![#(#tag_names.json()),*]);
+            let item_args = compile(vec![#(#arg_names.json()),*], vec![#(#tag_names.json(#unformated_action_name, #block_identifier)),*]);
 
             let mut args = serde_json::Map::new();
             args.insert("items".to_string(), serde_json::Value::Array(item_args));
 
+            map.insert("block".to_string(), serde_json::Value::String(#block_identifier.to_string()));
             map.insert("action".to_string(), serde_json::Value::String(#unformated_action_name.to_string()));
             map.insert("args".to_string(), serde_json::Value::Object(args));
 
@@ -297,36 +801,245 @@ fn gen_action(action: Action, used_names: &mut HashSet<String>, tags: &mut TagSe
         }
     );
 
-    (enum_var, (compile_function, tag_defs))
+    // The subaction owns the block's items, so it's read from the block with its own action put back.
+    let (subaction_reader, finish) = match &subaction_type {
+        Some(subaction_type) => (
+            quote!(subaction: {
+                let mut subaction = value.clone();
+                subaction["action"] = value.get("subaction").cloned().ok_or(DecodeError::MissingField("subaction"))?;
+                #subaction_type::from_json(&subaction)?
+            },),
+            quote!(),
+        ),
+        None => (quote!(), quote!(reader.finish()?;)),
+    };
+    let reader = match (arg_names.is_empty() && tag_names.is_empty(), &subaction_type) {
+        (true, Some(_)) => quote!(),
+        (true, None) => quote!(let reader = ArgReader::new(value, #unformated_action_name)?;),
+        (false, _) => quote!(let mut reader = ArgReader::new(value, #unformated_action_name)?;),
+    };
+    let decoder = quote!(
+        #unformated_action_name => {
+            #reader
+            let action = #block_name::#action_name {
+                #subaction_reader
+                #(#arg_names: #arg_readers,)*
+                #(#tag_names: #tag_readers),*
+            };
+            #finish
+            Ok(action)
+        }
+    );
+
+    let returns = quote!(
+        #block_name::#action_name { .. } => &[#(ReturnValue { type_name: #return_type_names, description: #return_descriptions }),*]
+    );
+
+    Ok(ActionCode { variant: enum_var, compile: compile_function, decode: decoder, returns, has_output: !return_types.is_empty() && output_type.is_none(), tags: tag_enums })
 }
 
-struct TagSet {
-    tags: Vec<Tag>,
-    tags_by_name: HashMap<String, usize>,
+/// The fields generated for the tags of an action.
+struct TagCode {
+    /// The tag fields with their types.
+    fields: Vec<token_stream::TokenStream>,
+    /// The names of the tag fields.
+    names: Vec<Ident>,
+    /// The expressions reading each field back from an `ArgReader`.
+    readers: Vec<token_stream::TokenStream>,
+    /// The tag enums the fields use.
+    types: Vec<Ident>,
 }
 
-impl TagSet {
-    fn new() -> Self {
-        Self {
-            tags: Vec::new(),
-            tags_by_name: HashMap::new(),
+/// Turns the tag list of an action into a list of fields typed with the shared tag enums from `tag_set`.
+fn gen_tags(tags: &[ActionTags], tag_set: &TagSet, location: &Location, arg_names: &[Ident]) -> Result<TagCode, GenError> {
+    let mut tag_fields = Vec::new();
+    let mut tag_names = Vec::new();
+    let mut tag_readers = Vec::new();
+    let mut tag_types = Vec::new();
+    for (tag_index, tag) in tags.iter().enumerate() {
+        let tag_location = location.join(&format!("tags[{}]", tag_index));
+        let tag_type = ident(tag_set.type_name(tag), || tag_location.join("name"))?;
+        let tag_ident = ident(&format!("{}_tag", field_name(&tag.name)), || tag_location.join("name"))?;
+        if tag_names.contains(&tag_ident) || arg_names.contains(&tag_ident) {
+            return Err(GenError::IdentCollision { location: tag_location.join("name"), ident: tag_ident.to_string() });
         }
+        let tag_name_unformated = &tag.name;
+        tag_fields.push(quote!(#tag_ident: #tag_type));
+        tag_names.push(tag_ident);
+        tag_readers.push(quote!(#tag_type::from_json(reader.tag(#tag_name_unformated)?)?));
+        tag_types.push(tag_type);
     }
+    Ok(TagCode { fields: tag_fields, names: tag_names, readers: tag_readers, types: tag_types })
 }
 
-struct Tag {
-    pub name: String,
-    pub action_name: String,
-    pub block_name: String,
-    pub tags: HashSet<String>,
-    pub name_style: TagStyle,
+/// Generates the enum variant for a dynamic action, ie. call function and start process.
+/// These take the name of the function or process to run rather than any arguments.
+fn gen_dynamic_action(action: &Action, codeblock: &CodeBlock, action_name: &str, location: &Location, tags: &TagSet) -> Result<ActionCode, GenError> {
+    let block_name = ident(&snake_to_camel_case(&module_name(&codeblock.name)), || location.join("codeblockName"))?;
+    let block_identifier = &codeblock.identifier;
+    let action_name = ident(action_name, || location.join("codeblockName"))?;
+    let unformated_action_name = action.name.clone();
+
+    // "CALL FUNCTION" takes a function_name, "START PROCESS" takes a process_name.
+    let target = action.codeblock_name.rsplit(' ').next().unwrap_or_default();
+    let target_name = ident(&format!("{}_name", field_name(target)), || location.join("codeblockName"))?;
+
+    let TagCode { fields: tag_types, names: tag_names, readers: tag_readers, types: tag_enums } = gen_tags(&action.tags, tags, location, std::slice::from_ref(&target_name))?;
+
+    let docs = doc_attrs(&action_docs(&action.icon));
+    let deprecated = deprecated_attr(action_deprecated_note(&action.icon));
+    let enum_var = quote!(
+        #docs
+        #deprecated
+        #action_name {
+            #target_name: String,
+            #(#tag_types),*
+        }
+    );
+
+    let compile_function = quote!(
+        #block_name::#action_name {#target_name, #(#tag_names),*} => {
+            let mut map = serde_json::Map::new();
+            let item_args = compile(vec![], vec![#(#tag_names.json(#unformated_action_name, #block_identifier)),*]);
+
+            let mut args = serde_json::Map::new();
+            args.insert("items".to_string(), serde_json::Value::Array(item_args));
+
+            map.insert("block".to_string(), serde_json::Value::String(#block_identifier.to_string()));
+            map.insert("data".to_string(), serde_json::Value::String(#target_name.clone()));
+            map.insert("args".to_string(), serde_json::Value::Object(args));
+
+            serde_json::Value::Object(map)
+        }
+    );
+
+    let reader_mut = if tag_names.is_empty() { quote!() } else { quote!(mut) };
+    let decoder = quote!(
+        #unformated_action_name => {
+            let #reader_mut reader = ArgReader::new(value, #unformated_action_name)?;
+            let action = #block_name::#action_name {
+                #target_name: value.get("data").and_then(Value::as_str).ok_or(DecodeError::MissingField("data"))?.to_string(),
+                #(#tag_names: #tag_readers),*
+            };
+            reader.finish()?;
+            Ok(action)
+        }
+    );
+
+    let returns = quote!(#block_name::#action_name { .. } => &[]);
+
+    Ok(ActionCode { variant: enum_var, compile: compile_function, decode: decoder, returns, has_output: false, tags: tag_enums })
+}
+
+/// The module a codeblock's actions are generated in, eg. "PLAYER ACTION" becomes player_action.
+fn module_name(codeblock_name: &str) -> String {
+    codeblock_name.to_ascii_lowercase().replace(" ", "_")
+}
+
+/// Turns an argument description or tag name into a snake case field name.
+fn field_name(name: &str) -> String {
+    remove_leading_nonalpha(name).replace(" ", "_").replace(|c: char| {!c.is_ascii_alphanumeric() && c != '_'}, "").to_lowercase().replace("type", "type_")
+}
+
+/// Creates an identifier, reporting names that aren't valid rust identifiers as a schema error at `location`.
+fn ident(name: &str, location: impl FnOnce() -> Location) -> Result<Ident, GenError> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "_";
+    if !valid {
+        return Err(schema_error(location(), &format!("{:?} is not a valid identifier", name)));
+    }
+    Ok(format_ident!("{}", name))
+}
+
+/// Turns lines of text into `///` doc comments, stripping colour codes on the way.
+fn doc_attrs<S: AsRef<str>>(lines: &[S]) -> token_stream::TokenStream {
+    let lines = lines.iter().map(|line| {
+        let line = strip_colour(line.as_ref());
+        if line.is_empty() { line } else { format!(" {}", line) }
+    });
+    quote!(#(#[doc = #lines])*)
+}
+
+/// The doc comment of an action, from its icon.
+fn action_docs(icon: &ActionIcon) -> Vec<String> {
+    icon_docs(&icon.description, &icon.example, &icon.works_with, &icon.additional_info)
+}
+
+/// The rank, token, rank and token and advanced requirements of an action, from its icon.
+fn action_requirements(action: &Action, location: &Location) -> Result<(Rank, bool, bool, bool), GenError> {
+    let icon = &action.icon;
+    let rank = icon.required_rank.parse::<Rank>().map_err(|message| schema_error(location.join("icon.requiredRank"), &message))?;
+    Ok((rank, icon.require_tokens, icon.require_rank_and_tokens, icon.advanced))
+}
+
+/// The deprecation note of an action, from its icon.
+fn action_deprecated_note(icon: &ActionIcon) -> &[String] {
+    &icon.deprecated_note
+}
+
+/// A `#[deprecated]` attribute carrying the note, if there is one.
+fn deprecated_attr<S: AsRef<str>>(note: &[S]) -> token_stream::TokenStream {
+    if note.is_empty() {
+        return quote!();
+    }
+    let note = note.iter().map(|line| strip_colour(line.as_ref())).collect::<Vec<_>>().join(" ");
+    quote!(#[deprecated(note = #note)])
+}
+
+/// The doc comment of an action or tag option, laid out as DiamondFire's own item tooltips are.
+fn icon_docs(description: &[String], example: &[String], works_with: &[String], additional_info: &[Vec<String>]) -> Vec<String> {
+    let mut lines = description.to_vec();
+    let mut section = |title: &str, items: Vec<String>| {
+        if items.is_empty() {
+            return;
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("**{}:**", title));
+        lines.extend(items);
+    };
+    section("Example", example.to_vec());
+    section("Works with", works_with.iter().map(|item| format!("- {}", item)).collect());
+    section("Additional info", additional_info.iter().map(|info| format!("- {}", info.join(" "))).collect());
+    lines
+}
+
+/// The doc comment of an argument's field, from its description, the descriptions of any types it can be instead and its notes.
+fn arg_docs(description: &[String], alternatives: &[String], notes: &[Vec<String>]) -> Vec<String> {
+    let mut lines = description.to_vec();
+    lines.extend(alternatives.iter().map(|alternative| format!("Or: {}", alternative)));
+    if !notes.is_empty() {
+        lines.push(String::new());
+        lines.extend(notes.iter().map(|note| format!("- {}", note.join(" "))));
+    }
+    lines
+}
+
+fn config_error(message: &str) -> GenError {
+    GenError::Config { message: message.to_string() }
 }
 
-enum TagStyle {
-    Unique,
-    UniqueWithBlock,
-    UniqueWithAction,
-    SharedNonUnique,
+/// The identifier of the module that would be called `default`, after any renaming.
+fn module_ident(config: &GenConfig, default: &str) -> Result<Ident, GenError> {
+    let name = config.resolve_module_name(default);
+    ident(&name, || Location::root("$")).map_err(|_| config_error(&format!("{:?} is not a valid module name", name)))
+}
+
+/// Checks that a config value parses as a `T` and turns it into tokens.
+fn parse_config<T: syn::parse::Parse>(value: &str, kind: &str) -> Result<token_stream::TokenStream, GenError> {
+    syn::parse_str::<T>(value)
+        .and_then(|_| syn::parse_str::<token_stream::TokenStream>(value))
+        .map_err(|err| config_error(&format!("invalid {} {:?}: {}", kind, value, err)))
+}
+
+fn schema_error(location: Location, message: &str) -> GenError {
+    GenError::Schema { location, message: message.to_string() }
+}
+
+fn unknown_arg_type(location: Location, arg_type: &str) -> GenError {
+    GenError::UnknownArgType { location, arg_type: arg_type.to_string() }
 }
 
 fn remove_leading_nonalpha(name: &str) -> String {
@@ -357,17 +1070,15 @@ fn replace_numeric(name: &str) -> String {
 }
 
 fn format_name(name: &str) -> String {
-    let name = strip_colour(name).replace(" ", "")
+    strip_colour(name).replace(" ", "")
         .replace("=", "Eq")
         .replace("<", "LessThan")
         .replace(">", "GreaterThan")
-        .replace(|c: char| {!c.is_ascii_alphanumeric() && c != '_'}, "");
-
-    name
+        .replace(|c: char| {!c.is_ascii_alphanumeric() && c != '_'}, "")
 }
 
-fn arg_type_to_rust(arg_type: &str) -> token_stream::TokenStream {
-    match arg_type {
+fn arg_type_to_rust(arg_type: &str) -> Option<token_stream::TokenStream> {
+    Some(match arg_type {
         "NUMBER" => quote!(Number),
         "TEXT" => quote!(Text),
         "COMPONENT" => quote!(MiniMessage),
@@ -387,8 +1098,8 @@ fn arg_type_to_rust(arg_type: &str) -> token_stream::TokenStream {
         "DICT" => quote!(Dict),
         "LIST" => quote!(List),
         "VEHICLE" => quote!(Vehicle),
-        arg => todo!("arg type: {}", arg)
-    }
+        _ => return None,
+    })
 }
 
 fn strip_colour(s: &str) -> String {
@@ -427,4 +1138,464 @@ fn snake_to_camel_case(s: &str) -> String {
         }
     }
     output
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ACTION_DUMP: &str = include_str!("../../test_data/actiondump.json");
+
+    /// Writes `dump` to a fresh temporary directory and returns the dump path and output directory.
+    fn setup(name: &str, dump: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("saphire-typegen-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let out = dir.join("out");
+        fs::create_dir_all(&out).unwrap();
+        let dump_path = dir.join("actiondump.json");
+        fs::write(&dump_path, dump).unwrap();
+        (dump_path, out)
+    }
+
+    #[test]
+    fn test_gen_types() {
+        let (dump_path, out) = setup("gen", ACTION_DUMP);
+        let report = gen_types(dump_path, out.clone()).unwrap();
+
+        let player_action = report.modules.iter().find(|module| module.name == "player_action").unwrap();
+        assert_eq!(player_action.actions, 2);
+        assert!(out.join("player_action.rs").exists());
+    }
+
+    #[test]
+    fn test_dynamic_actions() {
+        let (dump_path, out) = setup("dynamic", ACTION_DUMP);
+        gen_types(dump_path, out.clone()).unwrap();
+
+        let start_process = fs::read_to_string(out.join("start_process.rs")).unwrap();
+        assert!(start_process.contains("process_name: String,"));
+        assert!(start_process.contains("\"data\".to_string()"));
+        let call_function = fs::read_to_string(out.join("call_function.rs")).unwrap();
+        assert!(call_function.contains("CallFunction { function_name: String }"));
+    }
+
+    #[test]
+    fn test_unknown_arg_type() {
+        let dump = ACTION_DUMP.replacen("\"SPAWN_EGG\"", "\"HOLOGRAM\"", 1);
+        let (dump_path, out) = setup("unknown-arg", &dump);
+
+        match gen_types(dump_path, out) {
+            Err(GenError::UnknownArgType { location, arg_type }) => {
+                assert_eq!(arg_type, "HOLOGRAM");
+                assert_eq!(location.action.as_deref(), Some("SpawnMob"));
+                assert_eq!(location.path, format!("actions[{}].icon.arguments[0].type", location.index.unwrap()));
+            }
+            other => panic!("expected an unknown arg type error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_codeblock() {
+        let dump = ACTION_DUMP.replacen("\"codeblockName\": \"CONTROL\"", "\"codeblockName\": \"TELEPORT\"", 1);
+        let (dump_path, out) = setup("unknown-block", &dump);
+
+        match gen_types(dump_path, out) {
+            Err(GenError::UnknownCodeblock { location, codeblock }) => {
+                assert_eq!(codeblock, "TELEPORT");
+                assert_eq!(location.action.as_deref(), Some("Wait"));
+            }
+            other => panic!("expected an unknown codeblock error, got {:?}", other),
+        }
+
+        // Actions aren't looked at unless they're generated.
+        let config = GenConfig::new().disable(Section::Actions);
+        let generated = generate_from_str(&dump, &config).unwrap();
+        assert!(generated.modules.iter().all(|module| module.actions == 0));
+    }
+
+    #[test]
+    fn test_new_codeblock() {
+        let dump = ACTION_DUMP
+            .replacen("\"codeblockName\": \"CONTROL\"", "\"codeblockName\": \"TELEPORT\"", 1)
+            .replacen("\"codeblocks\": [", "\"codeblocks\": [{\"name\": \"TELEPORT\", \"identifier\": \"teleport\", \"item\": {\"material\": \"ENDER_PEARL\", \"name\": \"Teleport\", \"deprecatedNote\": [], \"description\": [], \"example\": [], \"worksWith\": [], \"additionalInfo\": [], \"requiredRank\": \"\", \"requireTokens\": false, \"requireRankAndTokens\": false, \"advanced\": false, \"loadedItem\": \"\"}},", 1);
+        let (dump_path, out) = setup("new-block", &dump);
+        gen_types(dump_path, out.clone()).unwrap();
+
+        let teleport = fs::read_to_string(out.join("teleport.rs")).unwrap();
+        assert!(teleport.contains("pub enum Teleport"));
+        assert!(teleport.contains("Value::String(\"teleport\".to_string())"));
+        let block_kind = fs::read_to_string(out.join("block_kind.rs")).unwrap();
+        assert!(block_kind.contains("BlockKind::Teleport => \"teleport\""));
+    }
+
+    #[test]
+    fn test_game_values() {
+        let (dump_path, out) = setup("game-values", ACTION_DUMP);
+        gen_types(dump_path, out.clone()).unwrap();
+
+        let game_value = fs::read_to_string(out.join("game_value.rs")).unwrap();
+        assert!(game_value.contains("GameValue::CurrentHealth => \"Current Health\""));
+        assert!(game_value.contains("GameValue::CurrentHealth => GameValueCategory::StatisticalValues"));
+        assert!(game_value.contains("GameValue::EyeLocation => \"Location\""));
+
+        let dump = ACTION_DUMP.replacen("\"name\": \"Current Health\"", "\"name\": \"2D Health\"", 1);
+        let sources = generate_from_str(&dump, &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["game_value"].contains("GameValue::TwoDHealth => \"2D Health\""));
+    }
+
+    #[test]
+    fn test_unknown_game_value_category() {
+        let dump = ACTION_DUMP.replacen("\"category\": \"Statistical Values\"", "\"category\": \"Plot Values\"", 1);
+        let (dump_path, out) = setup("game-value-category", &dump);
+
+        match gen_types(dump_path, out) {
+            Err(GenError::Schema { location, .. }) => assert_eq!(location.path, "gameValues[0].category"),
+            other => panic!("expected a schema error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_particles_sounds_potions() {
+        let (dump_path, out) = setup("particles", ACTION_DUMP);
+        gen_types(dump_path, out.clone()).unwrap();
+
+        let particle = fs::read_to_string(out.join("particle.rs")).unwrap();
+        assert!(particle.contains("ParticleKind::AngryVillager => Some(ParticleCategory::Emotion)"));
+        assert!(particle.contains("\"Color Variation\","));
+        let sound = fs::read_to_string(out.join("sound.rs")).unwrap();
+        assert!(sound.contains("SoundKind::ZombieHurt => \"Zombie Hurt\""));
+        let potion = fs::read_to_string(out.join("potion.rs")).unwrap();
+        assert!(potion.contains("PotionKind::Slowness => \"Slowness\""));
+    }
+
+    #[test]
+    fn test_empty_sections() {
+        let config = GenConfig::new().load_mode(LoadMode::Lenient);
+        let sources = generate_from_str("{\"actions\": []}", &config).unwrap().sources().unwrap();
+        for (module, enum_name) in [("game_value", "GameValue"), ("particle", "ParticleKind"), ("sound", "SoundKind"), ("potion", "PotionKind")] {
+            assert!(sources[module].contains(&format!("pub enum {} {{}}", enum_name)), "{}", sources[module]);
+            assert!(sources[module].contains("match *self {}"), "{}", sources[module]);
+        }
+        assert!(sources["block_kind"].contains("match *self {}"));
+    }
+
+    /// Reads every file generated in `out`, keyed by file name.
+    fn read_output(out: &PathBuf) -> Vec<(String, String)> {
+        let mut files = fs::read_dir(out).unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                (path.file_name().unwrap().to_string_lossy().to_string(), fs::read_to_string(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_deterministic_output() {
+        let (dump_path, out) = setup("deterministic-a", ACTION_DUMP);
+        let first = gen_types(dump_path, out.clone()).unwrap();
+        let (dump_path, other_out) = setup("deterministic-b", ACTION_DUMP);
+        let second = gen_types(dump_path, other_out.clone()).unwrap();
+
+        let module_names = |report: &GenReport| report.modules.iter().map(|module| module.name.clone()).collect::<Vec<_>>();
+        assert_eq!(module_names(&first), module_names(&second));
+        assert_eq!(read_output(&out), read_output(&other_out));
+    }
+
+    #[test]
+    fn test_collisions_ignore_dump_order() {
+        // ActionBar shares its icon name with SendMessage, SendMessage keeps the name whichever comes first.
+        let mut json = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let actions = json["actions"].as_array_mut().unwrap();
+        let send_message = actions.iter().position(|action| action["name"] == "SendMessage").unwrap();
+        let mut action_bar = actions[send_message].clone();
+        action_bar["name"] = "ActionBar".into();
+        actions.insert(send_message, action_bar);
+        let (dump_path, out) = setup("collisions-a", &json.to_string());
+        gen_types(dump_path, out.clone()).unwrap();
+
+        json["actions"].as_array_mut().unwrap().reverse();
+        let (dump_path, reversed_out) = setup("collisions-b", &json.to_string());
+        gen_types(dump_path, reversed_out.clone()).unwrap();
+
+        let player_action = fs::read_to_string(out.join("player_action.rs")).unwrap();
+        assert!(player_action.contains("PlayerAction::SendMessage {"));
+        assert!(player_action.contains("PlayerAction::ActionBar {"));
+        let reversed_player_action = fs::read_to_string(reversed_out.join("player_action.rs")).unwrap();
+        assert!(reversed_player_action.contains("PlayerAction::SendMessage {"));
+        assert!(reversed_player_action.contains("PlayerAction::ActionBar {"));
+    }
+
+    #[test]
+    fn test_config() {
+        let (dump_path, out) = setup("config", ACTION_DUMP);
+        let config = GenConfig::new()
+            .imports(["my_crate::values::*"])
+            .derive("PartialEq")
+            .module_name("player_action", "player")
+            .layout(OutputLayout::FilesWithMod)
+            .disable(Section::Sounds);
+        let report = gen_with_config(dump_path, out.clone(), &config).unwrap();
+
+        assert!(report.modules.iter().all(|module| module.name != "sound"));
+        let player = fs::read_to_string(out.join("player.rs")).unwrap();
+        assert!(player.contains("use my_crate::values::*;"));
+        assert!(!player.contains("use crate::types::*;"));
+        assert!(player.contains("#[derive(PartialEq)]\npub enum PlayerAction"));
+        let tags = fs::read_to_string(out.join("tags.rs")).unwrap();
+        assert!(tags.contains("#[derive(Debug, Clone, PartialEq)]"));
+
+        let config = GenConfig::new().derive("Debug").derive("std::clone::Clone").derive("PartialEq");
+        let sources = generate_from_str(ACTION_DUMP, &config).unwrap().sources().unwrap();
+        assert!(sources["player_action"].contains("#[derive(Debug, std::clone::Clone, PartialEq)]\npub enum PlayerAction"));
+        assert!(sources["tags"].contains("#[derive(Debug, Clone, PartialEq)]\npub enum AlignmentMode"));
+        let module = fs::read_to_string(out.join("mod.rs")).unwrap();
+        assert!(module.contains("pub mod player;"));
+        assert!(!module.contains("pub mod sound;"));
+    }
+
+    #[test]
+    fn test_single_file_layout() {
+        let (dump_path, out) = setup("single-file", ACTION_DUMP);
+        let config = GenConfig::new().layout(OutputLayout::SingleFile("generated.rs".to_string()));
+        gen_with_config(dump_path, out.clone(), &config).unwrap();
+
+        let generated = fs::read_to_string(out.join("generated.rs")).unwrap();
+        assert!(generated.contains("pub mod player_action {"));
+        assert!(generated.contains("pub mod block_kind {"));
+        assert!(!out.join("player_action.rs").exists());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let (dump_path, out) = setup("invalid-config", ACTION_DUMP);
+        let config = GenConfig::new().derive("Partial Eq");
+
+        assert!(matches!(gen_with_config(dump_path, out, &config), Err(GenError::Config { .. })));
+    }
+
+    #[test]
+    fn test_generate_in_memory() {
+        let generated = generate_from_str(ACTION_DUMP, &GenConfig::default()).unwrap();
+        let sources = generated.sources().unwrap();
+        assert!(sources["control"].contains("pub enum Control"));
+        assert!(generated.tokens()["block_kind"].to_string().contains("enum BlockKind"));
+
+        let dump = serde_json::from_str::<ActionDump>(ACTION_DUMP).unwrap();
+        let from_dump = generate(&dump, &GenConfig::default()).unwrap();
+        assert_eq!(from_dump.sources().unwrap(), sources);
+    }
+
+    #[test]
+    fn test_decoders() {
+        let sources = generate_from_str(ACTION_DUMP, &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["decode"].contains("pub enum DecodeError"));
+        assert!(sources["decode"].contains("pub struct ArgReader"));
+
+        let player_action = &sources["player_action"];
+        assert!(player_action.contains("use super::decode::{block_action, ArgReader, DecodeError};"));
+        assert!(player_action.contains("pub fn from_json(value: &Value) -> Result<Self, DecodeError>"));
+        assert!(player_action.contains("\"SendMessage\" =>"));
+        assert!(player_action.contains("reader.plural()?"));
+        assert!(player_action.contains("reader.tag(\"Alignment Mode\")"));
+        assert!(sources["tags"].contains("\"Centered\" => Ok(AlignmentMode::Centered)"));
+
+        assert!(sources["call_function"].contains("\"dynamic\" =>"));
+        assert!(sources["select_object"].contains("SelectPlayer::from_json(&subaction)?"));
+
+        let renamed = generate_from_str(ACTION_DUMP, &GenConfig::new().module_name("decode", "decompile")).unwrap().sources().unwrap();
+        assert!(renamed["player_action"].contains("use super::decompile::"));
+    }
+
+    #[test]
+    fn test_shared_tags() {
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let actions = dump["actions"].as_array_mut().unwrap();
+        let icon = actions.iter().find(|action| action["name"] == "SendMessage").unwrap()["tags"][0]["options"][0]["icon"].clone();
+        let tag = |name: &str, options: &[&str]| serde_json::json!({
+            "name": name,
+            "options": options.iter().map(|option| serde_json::json!({"name": option, "icon": icon, "aliases": []})).collect::<Vec<_>>(),
+            "defaultOption": options[0],
+            "slot": 26,
+        });
+        let spawn_mob = actions.iter_mut().find(|action| action["name"] == "SpawnMob").unwrap();
+        spawn_mob["tags"] = serde_json::json!([tag("Shape", &["Sphere", "Cube"])]);
+        spawn_mob["icon"]["tags"] = 1.into();
+
+        // Two blocks with differently optioned tags of the same name.
+        let sources = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap().sources().unwrap();
+        assert_eq!(sources["tags"].matches("pub enum AlignmentMode {").count(), 1);
+        assert!(sources["tags"].contains("pub enum ShapeIfPlayer {"));
+        assert!(sources["tags"].contains("pub enum ShapeGameAction {"));
+        assert!(sources["game_action"].contains("shape_tag: ShapeGameAction"));
+        assert!(sources["player_action"].contains("use super::tags::{AlignmentMode, InheritStyles, ReducedDebugInfoEnabled};"));
+        assert!(sources["player_action"].contains("alignment_mode_tag.json(\"SendMessage\", \"player_action\")"));
+
+        // Another action in the same block shares its tags with actions elsewhere.
+        let actions = dump["actions"].as_array_mut().unwrap();
+        let mut broadcast = actions.iter().find(|action| action["name"] == "SendMessage").unwrap().clone();
+        broadcast["name"] = "Broadcast".into();
+        broadcast["codeblockName"] = "GAME ACTION".into();
+        broadcast["icon"]["name"] = "Broadcast".into();
+        broadcast["tags"].as_array_mut().unwrap().push(tag("Shape", &["Sphere", "Circle"]));
+        broadcast["icon"]["tags"] = 3.into();
+        actions.push(broadcast);
+
+        let sources = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap().sources().unwrap();
+        assert_eq!(sources["tags"].matches("pub enum AlignmentMode {").count(), 1);
+        assert!(sources["tags"].contains("pub enum ShapeGameActionBroadcast {"));
+        assert!(sources["tags"].contains("pub enum ShapeSpawnMob {"));
+        assert!(sources["if_player"].contains("shape_tag: ShapeGameActionBroadcast"));
+        assert!(sources["game_action"].contains("alignment_mode_tag: AlignmentMode"));
+    }
+
+    #[test]
+    fn test_docs() {
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let send_message = dump["actions"].as_array_mut().unwrap().iter_mut().find(|action| action["name"] == "SendMessage").unwrap();
+        send_message["icon"]["example"] = serde_json::json!(["§7Hello world!"]);
+        send_message["icon"]["worksWith"] = serde_json::json!(["Players"]);
+        send_message["icon"]["additionalInfo"] = serde_json::json!([["Messages support", "MiniMessage."]]);
+        send_message["icon"]["arguments"][0]["notes"] = serde_json::json!([["Sent in order."]]);
+        send_message["tags"][0]["options"][1]["icon"]["description"] = serde_json::json!(["§eCenters the message."]);
+
+        let sources = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap().sources().unwrap();
+        let player_action = &sources["player_action"];
+        assert!(player_action.contains("/// Sends a chat message to the player.\n    ///\n    /// **Example:**\n    /// Hello world!\n"));
+        assert!(player_action.contains("/// **Works with:**\n    /// - Players\n"));
+        assert!(player_action.contains("/// - Messages support MiniMessage.\n"));
+        assert!(player_action.contains("/// Message to send\n        ///\n        /// - Sent in order.\n        message_to_send"));
+        assert!(sources["tags"].contains("/// Centers the message.\n    Centered"));
+        assert!(sources["game_action"].contains("/// Or: "));
+    }
+
+    #[test]
+    fn test_deprecated() {
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let actions = dump["actions"].as_array_mut().unwrap();
+        let set_reduced_debug = actions.iter_mut().find(|action| action["name"] == "SetReducedDebug").unwrap();
+        set_reduced_debug["icon"]["deprecatedNote"] = serde_json::json!(["§cThis action is", "no longer supported."]);
+        let send_message = actions.iter_mut().find(|action| action["name"] == "SendMessage").unwrap();
+        send_message["tags"][0]["options"][1]["icon"]["deprecatedNote"] = serde_json::json!(["Use Regular."]);
+
+        let sources = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["player_action"].contains("#[deprecated(note = \"This action is no longer supported.\")]\n    SetReducedDebug"));
+        assert_eq!(sources["player_action"].matches("#[deprecated").count(), 1);
+        assert!(sources["player_action"].contains("#[allow(deprecated)]\nimpl PlayerAction"));
+        assert!(sources["tags"].contains("#[deprecated(note = \"Use Regular.\")]\n    Centered"));
+    }
+
+    #[test]
+    fn test_requirements() {
+        let sources = generate_from_str(ACTION_DUMP, &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["requirements"].contains("pub enum Rank"));
+        assert!(sources["player_action"].contains("pub fn requirements(&self) -> Requirements"));
+        assert!(sources["player_action"].contains("rank: Rank::Noble"));
+        assert!(sources["game_action"].contains("rank: Rank::Emperor,\n                    tokens: true"));
+
+        let dump = ACTION_DUMP.replacen("\"requiredRank\": \"Noble\"", "\"requiredRank\": \"Admiral\"", 1);
+        let err = generate_from_str(&dump, &GenConfig::default()).unwrap_err();
+        assert!(matches!(&err, GenError::Schema { location, .. } if location.path.ends_with("icon.requiredRank")), "{}", err);
+    }
+
+    #[test]
+    fn test_max_rank() {
+        let unranked = generate_from_str(ACTION_DUMP, &GenConfig::new().max_rank(Rank::None)).unwrap().sources().unwrap();
+        assert!(!unranked["player_action"].contains("SetReducedDebug"));
+        assert!(unranked["player_action"].contains("SendMessage {"));
+        assert!(!unranked.contains_key("game_action"));
+
+        let noble = generate_from_str(ACTION_DUMP, &GenConfig::new().max_rank(Rank::Noble)).unwrap().sources().unwrap();
+        assert!(noble["player_action"].contains("SetReducedDebug"));
+        assert!(!noble.contains_key("game_action"));
+        let overlord = generate_from_str(ACTION_DUMP, &GenConfig::new().max_rank(Rank::Overlord)).unwrap().sources().unwrap();
+        assert!(overlord["game_action"].contains("SpawnMob {"));
+    }
+
+    #[test]
+    fn test_return_types() {
+        let sources = generate_from_str(ACTION_DUMP, &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["returns"].contains("pub struct Output<V, R>"));
+        let set_variable = &sources["set_variable"];
+        assert!(set_variable.contains("use super::returns::{Output, ReturnValue};"));
+        assert!(set_variable.contains("variable_to_set: Output<VariableLiteral, Number>"));
+        assert!(set_variable.contains("type_name: \"Number\""));
+        assert!(set_variable.contains("description: \"The sum of the numbers\""));
+        assert!(sources["player_action"].contains("PlayerAction::SendMessage { .. } => &[]"));
+        assert!(sources["player_action"].contains("use super::returns::ReturnValue;"));
+
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let add = dump["actions"].as_array_mut().unwrap().iter_mut().find(|action| action["name"] == "+").unwrap();
+        add["icon"]["returnValues"] = serde_json::json!([
+            {"type": "NUMBER", "description": ["A number"]},
+            {"text": "OR"},
+            {"type": "VECTOR", "description": ["A vector"]},
+        ]);
+        let sources = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap().sources().unwrap();
+        assert!(sources["set_variable"].contains("variable_to_set: Output<VariableLiteral, Either<Number, Vector>>"));
+
+        let add = dump["actions"].as_array_mut().unwrap().iter_mut().find(|action| action["name"] == "+").unwrap();
+        add["icon"]["returnValues"] = serde_json::json!([{"type": "BANANA", "description": []}]);
+        let err = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap_err();
+        assert!(matches!(&err, GenError::UnknownArgType { location, .. } if location.path.ends_with("icon.returnValues[0].type")), "{}", err);
+    }
+
+    #[test]
+    fn test_tag_count_mismatch() {
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let send_message = dump["actions"].as_array_mut().unwrap().iter_mut().find(|action| action["name"] == "SendMessage").unwrap();
+        send_message["tags"].as_array_mut().unwrap().pop();
+
+        let err = generate_from_str(&dump.to_string(), &GenConfig::default()).unwrap_err();
+        assert!(matches!(&err, GenError::Schema { location, .. } if location.path == "actions[1].icon.tags"), "{}", err);
+        assert!(err.to_string().contains("the icon lists 2 tags but the action has 1"));
+    }
+
+    #[test]
+    fn test_missing_dump() {
+        let (dump_path, out) = setup("missing", "");
+        fs::remove_file(&dump_path).unwrap();
+
+        assert!(matches!(gen_types(dump_path, out), Err(GenError::Io { .. })));
+    }
+
+    #[test]
+    fn test_api_changes() {
+        let old = serde_json::from_str::<ActionDump>(ACTION_DUMP).unwrap();
+        assert!(api_changes(&old, &old, &GenConfig::default()).unwrap().changes.is_empty());
+
+        let mut dump = serde_json::from_str::<serde_json::Value>(ACTION_DUMP).unwrap();
+        let actions = dump["actions"].as_array_mut().unwrap();
+        let send_message = actions.iter_mut().find(|action| action["name"] == "SendMessage").unwrap();
+        send_message["icon"]["arguments"][0]["description"][0] = "Text to send".into();
+        send_message["tags"][0]["options"][0]["name"] = "Plain".into();
+        send_message["tags"][0]["defaultOption"] = "Plain".into();
+        actions.retain(|action| action["name"] != "Join");
+        let new = serde_json::from_value::<ActionDump>(dump).unwrap();
+
+        let report = api_changes(&old, &new, &GenConfig::default()).unwrap();
+        assert!(!report.is_compatible());
+        let change = |path: &str| report.changes.iter().find(|change| change.path == path).map(|change| &change.kind);
+        assert_eq!(change("player_action::PlayerAction::SendMessage::message_to_send"), Some(&ApiChangeKind::FieldRenamed { new: "text_to_send".to_string() }));
+        // Join is the only player event, so its whole enum goes.
+        assert_eq!(change("player_event::PlayerEvent"), Some(&ApiChangeKind::EnumRemoved));
+        assert_eq!(change("tags::AlignmentMode::Regular"), Some(&ApiChangeKind::VariantRemoved));
+        assert_eq!(change("tags::AlignmentMode::Plain"), Some(&ApiChangeKind::VariantAdded));
+        // None of the generated enums are `#[non_exhaustive]`, so a new variant breaks exhaustive matches.
+        assert!(report.breaking().any(|change| change.path == "tags::AlignmentMode::Plain"));
+        assert!(report.non_breaking().all(|change| change.kind == ApiChangeKind::EnumAdded));
+    }
+
+    #[test]
+    fn test_describe_action() {
+        let dump = serde_json::from_str::<ActionDump>(ACTION_DUMP).unwrap();
+        assert!(describe_action(&dump, &GenConfig::default(), "NotAnAction").unwrap().is_empty());
+
+        let descriptions = describe_action(&dump, &GenConfig::default(), "sendmessage").unwrap();
+        assert_eq!(descriptions.len(), 1);
+        assert!(descriptions[0].starts_with("// player_action\n"));
+        assert!(descriptions[0].contains("pub enum PlayerAction {"));
+        assert!(descriptions[0].contains("pub enum AlignmentMode {"));
+        assert!(!descriptions[0].contains("SetReducedDebug"));
+    }
+}
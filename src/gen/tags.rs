@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use proc_macro2::{token_stream, Ident};
+use quote::quote;
+use crate::raw_types::ActionTags;
+use super::{deprecated_attr, doc_attrs, format_name, icon_docs, ident, remove_leading_nonalpha, replace_numeric, schema_error, snake_to_camel_case, GenError, Location};
+
+/// How a tag enum is named, depending on which other tags share its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TagStyle {
+    /// No other tag has its name, eg. `AlignmentMode`.
+    Unique,
+    /// No other tag in its block has its name, eg. `ShapeIfPlayer`.
+    UniqueWithBlock,
+    /// Only used by one action, eg. `ShapeIsNear`, or `ShapeIfPlayerIsNear` when an action of the same name in another block has one too.
+    UniqueWithAction,
+    /// Used by several actions, named after the first of them, eg. `ShapeIfPlayerIsNear`.
+    SharedNonUnique,
+}
+
+/// A tag, ie. a name with a set of options, along with every action using it.
+pub(super) struct Tag<'a> {
+    /// The first action's tag, which the enum's variants are named after.
+    pub tag: &'a ActionTags,
+    /// Where the first action's tag is in the dump.
+    pub location: Location,
+    /// The block enum and variant name of every action using the tag, in order.
+    pub actions: BTreeSet<(String, String)>,
+    pub name_style: TagStyle,
+    pub type_name: String,
+}
+
+/// Every distinct tag in the dump, so actions with identical tags share a single enum.
+pub(super) struct TagSet<'a> {
+    tags: Vec<Tag<'a>>,
+    tags_by_name: HashMap<String, Vec<usize>>,
+}
+
+impl<'a> TagSet<'a> {
+    pub fn new() -> Self {
+        Self {
+            tags: Vec::new(),
+            tags_by_name: HashMap::new(),
+        }
+    }
+
+    /// Adds a tag used by the action `action_name` of `block_name`.
+    pub fn insert(&mut self, tag: &'a ActionTags, block_name: &str, action_name: &str, location: Location) {
+        let user = (block_name.to_string(), action_name.to_string());
+        let indices = self.tags_by_name.entry(tag.name.clone()).or_default();
+        match indices.iter().find(|i| same_options(self.tags[**i].tag, tag)) {
+            Some(i) => {
+                let existing = &mut self.tags[*i];
+                // The tag is named after its first action, whichever order the dump lists them in.
+                if existing.actions.first().is_some_and(|first| &user < first) {
+                    existing.tag = tag;
+                    existing.location = location;
+                }
+                existing.actions.insert(user);
+            }
+            None => {
+                indices.push(self.tags.len());
+                self.tags.push(Tag {
+                    tag,
+                    location,
+                    actions: BTreeSet::from([user]),
+                    name_style: TagStyle::Unique,
+                    type_name: String::new(),
+                });
+            }
+        }
+    }
+
+    /// Picks the style and name of every tag, once every action's tags have been added.
+    pub fn resolve(&mut self) -> Result<(), GenError> {
+        for indices in self.tags_by_name.values() {
+            for i in indices {
+                let tag = &self.tags[*i];
+                let blocks = tag.actions.iter().map(|(block, _)| block).collect::<BTreeSet<_>>();
+                let shares_block = indices.iter()
+                    .filter(|other| *other != i)
+                    .any(|other| self.tags[*other].actions.iter().any(|(block, _)| blocks.contains(block)));
+
+                let name_style = if indices.len() == 1 {
+                    TagStyle::Unique
+                }
+                else if blocks.len() == 1 && !shares_block {
+                    TagStyle::UniqueWithBlock
+                }
+                else if tag.actions.len() == 1 {
+                    TagStyle::UniqueWithAction
+                }
+                else {
+                    TagStyle::SharedNonUnique
+                };
+                self.tags[*i].name_style = name_style;
+            }
+
+            for i in indices {
+                let tag = &self.tags[*i];
+                let name = format_name(&tag.tag.name);
+                let (block, action) = tag.actions.first().expect("a tag is only added along with an action");
+                // Actions of the same name in different blocks can each have a differently optioned tag.
+                let action_is_unique = indices.iter()
+                    .filter(|other| *other != i && self.tags[**other].name_style == TagStyle::UniqueWithAction)
+                    .all(|other| self.tags[*other].actions.iter().all(|(_, other_action)| other_action != action));
+
+                let type_name = match tag.name_style {
+                    TagStyle::Unique => name,
+                    TagStyle::UniqueWithBlock => format!("{}{}", name, block),
+                    TagStyle::UniqueWithAction if action_is_unique => format!("{}{}", name, action),
+                    TagStyle::UniqueWithAction | TagStyle::SharedNonUnique => format!("{}{}{}", name, block, action),
+                };
+                self.tags[*i].type_name = type_name;
+            }
+        }
+
+        let mut type_names = BTreeSet::new();
+        for tag in &self.tags {
+            ident(&tag.type_name, || tag.location.join("name"))?;
+            if !type_names.insert(&tag.type_name) {
+                return Err(GenError::IdentCollision { location: tag.location.join("name"), ident: tag.type_name.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// The enum generated for an action's tag.
+    pub fn type_name(&self, tag: &ActionTags) -> &str {
+        self.tags_by_name[&tag.name].iter()
+            .map(|i| &self.tags[*i])
+            .find(|other| same_options(other.tag, tag))
+            .map(|other| other.type_name.as_str())
+            .expect("every action's tags are added before generating it")
+    }
+
+    /// Generates the `tags` module, with an enum for every tag.
+    pub fn gen_module(&self, derives: &[token_stream::TokenStream], decode_module: &Ident) -> Result<token_stream::TokenStream, GenError> {
+        // Tag enums always derive Debug and Clone, so configuring either again mustn't derive it twice.
+        let derives = derives.iter()
+            .filter(|derive| !syn::parse2::<syn::Path>((*derive).clone())
+                .is_ok_and(|path| path.segments.last().is_some_and(|segment| segment.ident == "Debug" || segment.ident == "Clone")))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut tag_defs = BTreeMap::new();
+        for tag in &self.tags {
+            tag_defs.insert(&tag.type_name, gen_tag(tag, &derives)?);
+        }
+        let tag_defs = tag_defs.into_values();
+
+        Ok(quote!(
+            use serde_json::Value;
+            use super::#decode_module::DecodeError;
+
+            #(#tag_defs)*
+        ))
+    }
+}
+
+/// Whether two tags would generate the same enum.
+fn same_options(a: &ActionTags, b: &ActionTags) -> bool {
+    a.name == b.name
+        && a.default_option == b.default_option
+        && a.options.iter().map(|option| &option.name).eq(b.options.iter().map(|option| &option.name))
+}
+
+/// Generates the enum for a single tag.
+fn gen_tag(tag: &Tag, derives: &[token_stream::TokenStream]) -> Result<token_stream::TokenStream, GenError> {
+    let tag_location = &tag.location;
+    let tag_type = ident(&tag.type_name, || tag_location.join("name"))?;
+    let tag_name_unformated = &tag.tag.name;
+
+    let mut tag_default = None;
+    let mut tag_options = Vec::new();
+    let mut tag_strings = Vec::new();
+    let mut option_docs = Vec::new();
+    // Looked up in order, so an option's own name wins over another's alias.
+    let mut lookup_names = [Vec::new(), Vec::new()];
+    for (option_index, option) in tag.tag.options.iter().enumerate() {
+        let option_location = tag_location.join(&format!("options[{}]", option_index));
+        let option_name = if !format_name(&replace_numeric(&option.name)).is_empty() {
+            format_name(&replace_numeric(&option.name))
+        }
+        else if !option.aliases.is_empty() && !format_name(&remove_leading_nonalpha(&option.aliases[0])).is_empty() {
+            format_name(&replace_numeric(&option.aliases[0]))
+        }
+        else {
+            let description = option.icon.description.first()
+                .ok_or_else(|| schema_error(option_location.join("icon.description"), "tag option has no name, alias or description to name it after"))?;
+            snake_to_camel_case(&format_name(description))
+        };
+        let option_name = ident(&option_name, || option_location.clone())?;
+        if tag_options.contains(&option_name) {
+            return Err(GenError::IdentCollision { location: option_location, ident: option_name.to_string() });
+        }
+
+        if option.name == tag.tag.default_option {
+            tag_default = Some(option_name.clone());
+        }
+
+        lookup_names[0].push((option.name.clone(), option_name.clone()));
+        lookup_names[1].extend(option.aliases.iter().map(|alias| (alias.clone(), option_name.clone())));
+        tag_options.push(option_name.clone());
+        tag_strings.push(option.name.clone());
+        let docs = doc_attrs(&icon_docs(&option.icon.description, &option.icon.example, &option.icon.works_with, &option.icon.additional_info));
+        let deprecated = deprecated_attr(&option.icon.deprecated_note);
+        option_docs.push(quote!(#docs #deprecated));
+    }
+
+    let (lookup_strings, lookup_options): (Vec<_>, Vec<_>) = lookup_names.into_iter().flatten().unzip();
+    let tag_default = tag_default
+        .ok_or_else(|| schema_error(tag_location.join("defaultOption"), &format!("default option {:?} is not one of the tag's options", tag.tag.default_option)))?;
+
+    Ok(quote!(
+        #[derive(Debug, Clone #(, #derives)*)]
+        pub enum #tag_type {
+            #(#option_docs #tag_options),*
+        }
+
+        #[allow(deprecated)]
+        impl #tag_type {
+            /// The tag's json, as set on the given action of the given block.
+            pub fn json(&self, action: &str, block: &str) -> serde_json::Map<String, Value> {
+                let mut map = serde_json::Map::new();
+                let mut data = serde_json::Map::new();
+                data.insert("option".to_string(),
+                match self {
+                    #(#tag_type::#tag_options => Value::String(#tag_strings.to_string())),*
+                });
+                data.insert("tag".to_string(), Value::String(#tag_name_unformated.to_string()));
+                data.insert("action".to_string(), Value::String(action.to_string()));
+                data.insert("block".to_string(), Value::String(block.to_string()));
+
+                map.insert("data".to_string(), Value::Object(data));
+                map.insert("id".to_string(), Value::String("bl_tag".to_string()));
+                map
+            }
+
+            /// Reads a tag back from the json `json` produces for it.
+            pub fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                let data = value.get("data").ok_or(DecodeError::MissingField("data"))?;
+                let tag = data.get("tag").and_then(Value::as_str).ok_or(DecodeError::MissingField("tag"))?;
+                if tag != #tag_name_unformated {
+                    return Err(DecodeError::WrongTag { expected: #tag_name_unformated, found: tag.to_string() });
+                }
+                match data.get("option").and_then(Value::as_str).ok_or(DecodeError::MissingField("option"))? {
+                    #(#tag_strings => Ok(#tag_type::#tag_options),)*
+                    option => Err(DecodeError::UnknownTagOption { tag: #tag_name_unformated, option: option.to_string() }),
+                }
+            }
+
+            /// Looks an option up by its name or any of its aliases, ignoring case.
+            pub fn from_name(name: &str) -> Option<Self> {
+                [#((#lookup_strings, #tag_type::#lookup_options)),*].into_iter()
+                    .find(|(other, _)| other.eq_ignore_ascii_case(name))
+                    .map(|(_, option)| option)
+            }
+        }
+
+        #[allow(deprecated)]
+        impl Default for #tag_type {
+            fn default() -> Self {
+                Self::#tag_default
+            }
+        }
+    ))
+}
@@ -0,0 +1,333 @@
+use proc_macro2::{token_stream, Ident};
+use quote::quote;
+use crate::raw_types::{GameValue, GameValueCategory, Particle, ParticleCategory, Potion, Sound, SoundCategory};
+use super::{arg_type_to_rust, doc_attrs, format_name, ident, replace_numeric, schema_error, strip_colour, unknown_arg_type, GenError, Location};
+
+/// The targets a game value can be read from, these aren't part of the dump.
+const GAME_VALUE_TARGETS: [&str; 8] = ["Default", "Selection", "Killer", "Damager", "Shooter", "Victim", "Projectile", "LastEntity"];
+
+/// Generates the `GameValue` enum along with the `GameValueCategory` and `GameValueTarget` enums it uses.
+pub(super) fn gen_game_values(categories: &[GameValueCategory], game_values: &[GameValue]) -> Result<token_stream::TokenStream, GenError> {
+    let category_variants = variants(categories.iter().enumerate()
+        .map(|(i, category)| (category.identifier.as_str(), Location::root(&format!("gameValueCategories[{}].identifier", i)))))?;
+    let category_names = categories.iter().map(|category| category.identifier.clone()).collect::<Vec<_>>();
+
+    // Variants are grouped by category, in the order the categories are listed.
+    let mut ordered = game_values.iter().enumerate().collect::<Vec<_>>();
+    ordered.sort_by_key(|(_, value)| categories.iter().position(|category| category.identifier == value.category));
+
+    let variants = variants(ordered.iter()
+        .map(|(i, value)| (value.icon.name.as_str(), Location::root(&format!("gameValues[{}].icon.name", i)))))?;
+    let mut docs = Vec::new();
+    let mut names = Vec::new();
+    let mut value_categories = Vec::new();
+    let mut return_types = Vec::new();
+    for (i, value) in ordered {
+        let location = Location::root(&format!("gameValues[{}]", i));
+        let category = categories.iter().position(|category| category.identifier == value.category)
+            .ok_or_else(|| schema_error(location.join("category"), &format!("{:?} is not one of the game value categories", value.category)))?;
+        let return_type = arg_type_to_rust(&value.icon.return_type)
+            .ok_or_else(|| unknown_arg_type(location.join("icon.returnType"), &value.icon.return_type))?
+            .to_string();
+
+        let mut doc = value.icon.description.clone();
+        let returns = value.icon.return_description.join(" ");
+        doc.push(String::new());
+        if returns.is_empty() {
+            doc.push(format!("Returns a `{}`.", return_type));
+        }
+        else {
+            doc.push(format!("Returns a `{}`: {}", return_type, returns));
+        }
+
+        docs.push(doc_attrs(&doc));
+        names.push(strip_colour(&value.icon.name));
+        value_categories.push(category_variants[category].clone());
+        return_types.push(return_type);
+    }
+
+    let targets = GAME_VALUE_TARGETS.iter().map(|target| quote::format_ident!("{}", target)).collect::<Vec<_>>();
+    let target_names = GAME_VALUE_TARGETS.to_vec();
+
+    Ok(quote!(
+        use serde_json::Value;
+
+        /// Every game value, grouped by category.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum GameValue {
+            #(
+                #docs
+                #variants
+            ),*
+        }
+
+        impl GameValue {
+            /// The name of the game value as it appears in template json.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(GameValue::#variants => #names),*
+                }
+            }
+
+            pub fn category(&self) -> GameValueCategory {
+                match *self {
+                    #(GameValue::#variants => GameValueCategory::#value_categories),*
+                }
+            }
+
+            /// The name of the type this game value returns, eg. `Number`.
+            pub fn return_type(&self) -> &'static str {
+                match *self {
+                    #(GameValue::#variants => #return_types),*
+                }
+            }
+
+            pub fn json(&self, target: GameValueTarget) -> serde_json::Map<String, Value> {
+                let mut map = serde_json::Map::new();
+                let mut data = serde_json::Map::new();
+                data.insert("type".to_string(), Value::String(self.name().to_string()));
+                data.insert("target".to_string(), Value::String(target.name().to_string()));
+
+                map.insert("data".to_string(), Value::Object(data));
+                map.insert("id".to_string(), Value::String("g_val".to_string()));
+                map
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum GameValueCategory {
+            #(#category_variants),*
+        }
+
+        impl GameValueCategory {
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(GameValueCategory::#category_variants => #category_names),*
+                }
+            }
+        }
+
+        /// Who a game value is read from.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub enum GameValueTarget {
+            #[default]
+            #(#targets),*
+        }
+
+        impl GameValueTarget {
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(GameValueTarget::#targets => #target_names),*
+                }
+            }
+        }
+    ))
+}
+
+/// Generates the `ParticleKind` enum, listing every particle along with the fields it supports.
+pub(super) fn gen_particles(categories: &[ParticleCategory], particles: &[Particle]) -> Result<token_stream::TokenStream, GenError> {
+    let category_variants = variants(categories.iter().enumerate()
+        .map(|(i, category)| (category.identifier.as_str(), Location::root(&format!("particleCategories[{}].identifier", i)))))?;
+    let category_names = categories.iter().map(|category| category.identifier.clone()).collect::<Vec<_>>();
+
+    let variants = variants(particles.iter().enumerate()
+        .map(|(i, particle)| (particle.particle.as_str(), Location::root(&format!("particles[{}].particle", i)))))?;
+    let names = particles.iter().map(|particle| particle.particle.clone()).collect::<Vec<_>>();
+    let fields = particles.iter().map(|particle| particle.fields.clone()).collect::<Vec<_>>();
+    let particle_categories = particles.iter().enumerate()
+        .map(|(i, particle)| option_category(particle.category.as_deref(), &category_names, &category_variants, quote!(ParticleCategory), Location::root(&format!("particles[{}].category", i))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote!(
+        use serde_json::Value;
+
+        /// Every particle.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ParticleKind {
+            #(#variants),*
+        }
+
+        impl ParticleKind {
+            /// The name of the particle as it appears in template json.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(ParticleKind::#variants => #names),*
+                }
+            }
+
+            pub fn category(&self) -> Option<ParticleCategory> {
+                match *self {
+                    #(ParticleKind::#variants => #particle_categories),*
+                }
+            }
+
+            /// The fields this particle supports, eg. `Motion` or `Color`.
+            pub fn fields(&self) -> &'static [&'static str] {
+                match *self {
+                    #(ParticleKind::#variants => &[#(#fields),*]),*
+                }
+            }
+
+            /// A single particle with no extra data.
+            pub fn json(&self) -> serde_json::Map<String, Value> {
+                let mut map = serde_json::Map::new();
+                let mut data = serde_json::Map::new();
+                let mut cluster = serde_json::Map::new();
+                cluster.insert("amount".to_string(), Value::from(1));
+                cluster.insert("horizontal".to_string(), Value::from(0.0));
+                cluster.insert("vertical".to_string(), Value::from(0.0));
+                data.insert("particle".to_string(), Value::String(self.name().to_string()));
+                data.insert("cluster".to_string(), Value::Object(cluster));
+                data.insert("data".to_string(), Value::Object(serde_json::Map::new()));
+
+                map.insert("data".to_string(), Value::Object(data));
+                map.insert("id".to_string(), Value::String("part".to_string()));
+                map
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ParticleCategory {
+            #(#category_variants),*
+        }
+
+        impl ParticleCategory {
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(ParticleCategory::#category_variants => #category_names),*
+                }
+            }
+        }
+    ))
+}
+
+/// Generates the `SoundKind` enum, listing every sound.
+pub(super) fn gen_sounds(categories: &[SoundCategory], sounds: &[Sound]) -> Result<token_stream::TokenStream, GenError> {
+    let category_variants = variants(categories.iter().enumerate()
+        .map(|(i, category)| (category.identifier.as_str(), Location::root(&format!("soundCategories[{}].identifier", i)))))?;
+    let category_names = categories.iter().map(|category| category.identifier.clone()).collect::<Vec<_>>();
+
+    let variants = variants(sounds.iter().enumerate()
+        .map(|(i, sound)| (sound.sound.as_str(), Location::root(&format!("sounds[{}].sound", i)))))?;
+    let names = sounds.iter().map(|sound| sound.sound.clone()).collect::<Vec<_>>();
+    let sound_categories = sounds.iter().enumerate()
+        .map(|(i, sound)| option_category(sound.category.as_deref(), &category_names, &category_variants, quote!(SoundCategory), Location::root(&format!("sounds[{}].category", i))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote!(
+        use serde_json::Value;
+
+        /// Every sound.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum SoundKind {
+            #(#variants),*
+        }
+
+        impl SoundKind {
+            /// The name of the sound as it appears in template json.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(SoundKind::#variants => #names),*
+                }
+            }
+
+            pub fn category(&self) -> Option<SoundCategory> {
+                match *self {
+                    #(SoundKind::#variants => #sound_categories),*
+                }
+            }
+
+            pub fn json(&self, pitch: f64, volume: f64) -> serde_json::Map<String, Value> {
+                let mut map = serde_json::Map::new();
+                let mut data = serde_json::Map::new();
+                data.insert("sound".to_string(), Value::String(self.name().to_string()));
+                data.insert("pitch".to_string(), Value::from(pitch));
+                data.insert("vol".to_string(), Value::from(volume));
+
+                map.insert("data".to_string(), Value::Object(data));
+                map.insert("id".to_string(), Value::String("snd".to_string()));
+                map
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum SoundCategory {
+            #(#category_variants),*
+        }
+
+        impl SoundCategory {
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(SoundCategory::#category_variants => #category_names),*
+                }
+            }
+        }
+    ))
+}
+
+/// Generates the `PotionKind` enum, listing every potion effect.
+pub(super) fn gen_potions(potions: &[Potion]) -> Result<token_stream::TokenStream, GenError> {
+    let variants = variants(potions.iter().enumerate()
+        .map(|(i, potion)| (potion.potion.as_str(), Location::root(&format!("potions[{}].potion", i)))))?;
+    let names = potions.iter().map(|potion| potion.potion.clone()).collect::<Vec<_>>();
+
+    Ok(quote!(
+        use serde_json::Value;
+
+        /// Every potion effect.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum PotionKind {
+            #(#variants),*
+        }
+
+        impl PotionKind {
+            /// The name of the potion as it appears in template json.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(PotionKind::#variants => #names),*
+                }
+            }
+
+            /// A potion effect lasting `duration` ticks, with an `amplifier` of 0 being level 1.
+            pub fn json(&self, duration: i64, amplifier: i32) -> serde_json::Map<String, Value> {
+                let mut map = serde_json::Map::new();
+                let mut data = serde_json::Map::new();
+                data.insert("pot".to_string(), Value::String(self.name().to_string()));
+                data.insert("dur".to_string(), Value::from(duration));
+                data.insert("amp".to_string(), Value::from(amplifier));
+
+                map.insert("data".to_string(), Value::Object(data));
+                map.insert("id".to_string(), Value::String("pot".to_string()));
+                map
+            }
+        }
+    ))
+}
+
+/// Turns a list of names into enum variants, erroring if two of them end up the same.
+fn variants<'a>(names: impl Iterator<Item = (&'a str, Location)>) -> Result<Vec<Ident>, GenError> {
+    let mut variants = Vec::new();
+    for (name, location) in names {
+        let mut variant = format_name(name);
+        if variant.starts_with(|c: char| c.is_ascii_digit()) {
+            variant = replace_numeric(&variant);
+        }
+        let variant = ident(&variant, || location.clone())?;
+        if variants.contains(&variant) {
+            return Err(GenError::IdentCollision { location, ident: variant.to_string() });
+        }
+        variants.push(variant);
+    }
+    Ok(variants)
+}
+
+/// Turns an optional category name into `Some(Category::Variant)` or `None`.
+fn option_category(category: Option<&str>, names: &[String], variants: &[Ident], enum_name: token_stream::TokenStream, location: Location) -> Result<token_stream::TokenStream, GenError> {
+    let Some(category) = category else {
+        return Ok(quote!(None));
+    };
+    let index = names.iter().position(|name| name == category)
+        .ok_or_else(|| schema_error(location, &format!("{:?} is not one of the categories", category)))?;
+    let variant = &variants[index];
+    Ok(quote!(Some(#enum_name::#variant)))
+}
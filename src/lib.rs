@@ -1,2 +1,6 @@
+pub mod diff;
+pub mod drift;
 pub mod gen;
+pub mod load;
+pub mod query;
 pub mod raw_types;
\ No newline at end of file
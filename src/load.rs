@@ -0,0 +1,196 @@
+//! Loading the raw types in a [`LoadMode`], by walking the json alongside them before it's deserialized.
+//! The same walk records the [`Drift`] between a dump and the raw types for [`drift`](crate::drift).
+
+use std::str::FromStr;
+use serde::{de::{self, DeserializeOwned}, Serialize};
+use serde_json::{Map, Value};
+
+/// How the raw types treat fields the dump has but they don't, and fields they have but the dump doesn't.
+/// Every struct keeps the fields it doesn't know in its `extra` map, unless the mode rejects them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LoadMode {
+    /// Missing fields are an error, unknown fields are kept in `extra`. This is what plain serde deserialization does.
+    #[default]
+    Standard,
+    /// Missing and unknown fields are both an error, so additions to DiamondFire's schema get noticed.
+    Strict,
+    /// Missing fields are left at their default, unknown fields are kept in `extra`.
+    Lenient,
+}
+
+impl LoadMode {
+    pub const ALL: [LoadMode; 3] = [LoadMode::Standard, LoadMode::Strict, LoadMode::Lenient];
+
+    /// Deserializes a raw type from a json string in this mode.
+    pub fn from_str<T: RawType>(self, json: &str) -> Result<T, serde_json::Error> {
+        self.from_value(serde_json::from_str(json)?)
+    }
+
+    /// Deserializes a raw type from a json value in this mode.
+    pub fn from_value<T: RawType>(self, mut value: Value) -> Result<T, serde_json::Error> {
+        if self != LoadMode::Standard {
+            let mut walker = Walker::new(self);
+            T::walk(&mut value, &mut walker);
+            if !walker.unknown.is_empty() {
+                let fields = walker.unknown.iter().map(|field| format!("`{}`", field)).collect::<Vec<_>>();
+                return Err(de::Error::custom(format_args!("unknown field(s) {}", fields.join(", "))));
+            }
+        }
+        serde_json::from_value(value)
+    }
+}
+
+impl FromStr for LoadMode {
+    type Err = String;
+
+    /// Parses a mode from its name, in either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LoadMode::ALL.into_iter()
+            .find(|mode| format!("{:?}", mode).eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown load mode {:?}", s))
+    }
+}
+
+/// How a field in the dump differs from the raw types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftKind {
+    /// The dump has a field the raw type doesn't model, it ends up in `extra`.
+    Unmodelled,
+    /// The field holds a different kind of json value than the raw type expects.
+    TypeMismatch { expected: &'static str, found: &'static str },
+    /// The raw type has a field the dump doesn't.
+    Missing,
+}
+
+/// A single difference between the dump and the raw types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// The raw type the field belongs to, eg. `ActionArg`.
+    pub raw_type: &'static str,
+    /// Where the field is in the dump, eg. `actions[3].icon.arguments[0].range`.
+    pub path: String,
+    pub kind: DriftKind,
+}
+
+/// A type deserialized from the dump, which knows its fields so it can be loaded in any [`LoadMode`].
+pub trait RawType: DeserializeOwned {
+    /// Checks or fills in the fields of `value` and of the raw types nested in it, as the walker's mode requires.
+    fn walk(value: &mut Value, walker: &mut Walker);
+}
+
+/// Goes through a json value before it's deserialized, collecting unknown fields or filling in missing ones.
+/// It's also how [`drift::detect`](crate::drift::detect) compares the dump with the raw types.
+#[derive(Debug)]
+pub struct Walker {
+    mode: LoadMode,
+    path: Vec<String>,
+    unknown: Vec<String>,
+    drift: Option<Vec<Drift>>,
+}
+
+impl Walker {
+    fn new(mode: LoadMode) -> Self {
+        Self { mode, path: Vec::new(), unknown: Vec::new(), drift: None }
+    }
+
+    /// A walker that only records how the json differs from the raw types, leaving it untouched.
+    pub(crate) fn detecting() -> Self {
+        Self { drift: Some(Vec::new()), ..Self::new(LoadMode::Standard) }
+    }
+
+    pub(crate) fn into_drift(self) -> Vec<Drift> {
+        self.drift.unwrap_or_default()
+    }
+
+    /// Walks an object of the raw type `raw_type`, given its fields with their default values and the fields it may leave out.
+    /// Fields that are only in `optional` are known but have no default. `nested` then walks the raw types in its fields.
+    pub fn object(&mut self, value: &mut Value, raw_type: &'static str, defaults: Map<String, Value>, optional: &[&str], nested: impl FnOnce(&mut Map<String, Value>, &mut Walker)) {
+        let Value::Object(fields) = value else {
+            return;
+        };
+        if let Some(drift) = &mut self.drift {
+            for (name, found) in fields.iter() {
+                let kind = match defaults.get(name) {
+                    Some(Value::Null) => None,
+                    Some(default) if json_kind(default) != json_kind(found) => Some(DriftKind::TypeMismatch { expected: json_kind(default), found: json_kind(found) }),
+                    Some(_) => None,
+                    None if optional.contains(&name.as_str()) => None,
+                    None => Some(DriftKind::Unmodelled),
+                };
+                if let Some(kind) = kind {
+                    drift.push(Drift { raw_type, path: join_path(&self.path, name), kind });
+                }
+            }
+            for (name, default) in &defaults {
+                if !fields.contains_key(name) && !default.is_null() && !optional.contains(&name.as_str()) {
+                    drift.push(Drift { raw_type, path: join_path(&self.path, name), kind: DriftKind::Missing });
+                }
+            }
+        }
+        match self.mode {
+            LoadMode::Strict => {
+                for name in fields.keys() {
+                    if !defaults.contains_key(name) && !optional.contains(&name.as_str()) {
+                        self.unknown.push(join_path(&self.path, name));
+                    }
+                }
+            }
+            LoadMode::Lenient => {
+                for (name, default) in defaults {
+                    fields.entry(name).or_insert(default);
+                }
+            }
+            LoadMode::Standard => {}
+        }
+        nested(fields, self);
+    }
+
+    /// Walks the raw type in the field `name`, if there is one.
+    pub fn field<T: RawType>(&mut self, fields: &mut Map<String, Value>, name: &str) {
+        if let Some(value) = fields.get_mut(name) {
+            self.path.push(name.to_string());
+            T::walk(value, self);
+            self.path.pop();
+        }
+    }
+
+    /// Walks every raw type in the list in the field `name`, if there is one.
+    pub fn list<T: RawType>(&mut self, fields: &mut Map<String, Value>, name: &str) {
+        if let Some(Value::Array(items)) = fields.get_mut(name) {
+            for (i, item) in items.iter_mut().enumerate() {
+                self.path.push(format!("{}[{}]", name, i));
+                if let (Some(drift), false) = (&mut self.drift, item.is_object()) {
+                    let raw_type = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+                    drift.push(Drift { raw_type, path: self.path.join("."), kind: DriftKind::TypeMismatch { expected: "object", found: json_kind(item) } });
+                }
+                T::walk(item, self);
+                self.path.pop();
+            }
+        }
+    }
+}
+
+/// The dotted path of the field `name` of the object at `path`, eg. `actions[3].icon.name`.
+fn join_path(path: &[String], name: &str) -> String {
+    path.iter().map(String::as_str).chain([name]).collect::<Vec<_>>().join(".")
+}
+
+/// The kind of a json value, as named in drift reports.
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The fields of a type, with the values they default to.
+pub(crate) fn default_fields<T: Serialize + Default>() -> Map<String, Value> {
+    match serde_json::to_value(T::default()) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
@@ -0,0 +1,268 @@
+//! The `saphire-typegen` command, which generates, validates, compares and inspects action dumps.
+
+use std::{collections::BTreeMap, fs, path::Path, process::ExitCode};
+use serde::Deserialize;
+use saphire_typegen::{diff::diff, drift, gen::{self, GenConfig, OutputLayout, Rank, Section}, query::Query, raw_types::{ActionDump, LoadMode}};
+
+const USAGE: &str = "\
+Usage:
+    saphire-typegen generate <dump> <out-dir> [--config <file>]
+    saphire-typegen validate <dump> [--strict] [--config <file>]
+    saphire-typegen diff <old> <new> [--api] [--config <file>]
+    saphire-typegen show <action-name> [--dump <file>] [--config <file>]
+    saphire-typegen query [--dump <file>] [--codeblock <name>] [--name <name>] [--arg-type <type>]
+                          [--tag <name>] [--tag-option <name>] [--rank <rank>]
+
+The config file is json, eg.
+    { \"imports\": [\"crate::types::*\"], \"derives\": [\"PartialEq\"], \"moduleNames\": { \"player_action\": \"player\" },
+      \"layout\": \"files_with_mod\", \"sections\": [\"Actions\"], \"maxRank\": \"Noble\", \"loadMode\": \"Strict\" }
+`show` and `query` read actiondump.json from the current directory unless given --dump.";
+
+/// Why a command failed, usage errors are followed by the usage.
+enum Error {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Failed(message)
+    }
+}
+
+/// The command line, split into positional arguments and `--options`.
+struct Args {
+    positional: Vec<String>,
+    options: BTreeMap<String, Option<String>>,
+}
+
+impl Args {
+    /// Options that take a value, every other option is a flag.
+    const VALUED: [&'static str; 8] = ["config", "dump", "codeblock", "name", "arg-type", "tag", "tag-option", "rank"];
+    const FLAGS: [&'static str; 3] = ["strict", "api", "help"];
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = BTreeMap::new();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                positional.push(arg);
+                continue;
+            };
+            let value = if Self::VALUED.contains(&name) {
+                Some(args.next().ok_or_else(|| format!("--{} needs a value", name))?)
+            }
+            else if Self::FLAGS.contains(&name) {
+                None
+            }
+            else {
+                return Err(format!("unknown option --{}", name));
+            };
+            options.insert(name.to_string(), value);
+        }
+        Ok(Self { positional, options })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(Option::as_deref)
+    }
+
+    /// The positional arguments after the subcommand, which must be exactly `N`.
+    fn expect<const N: usize>(&self) -> Result<[&str; N], Error> {
+        let args = self.positional.iter().skip(1).map(String::as_str).collect::<Vec<_>>();
+        args.try_into().map_err(|args: Vec<_>| Error::Usage(format!("{} takes {} argument(s), got {}", self.positional[0], N, args.len())))
+    }
+}
+
+/// The json config file, each field maps to the [`GenConfig`] builder method of the same name.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigFile {
+    imports: Option<Vec<String>>,
+    #[serde(default)]
+    derives: Vec<String>,
+    #[serde(default)]
+    module_names: BTreeMap<String, String>,
+    layout: Option<Layout>,
+    sections: Option<Vec<String>>,
+    max_rank: Option<String>,
+    load_mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Layout {
+    Files,
+    FilesWithMod,
+    SingleFile(String),
+}
+
+impl ConfigFile {
+    /// The config along with the mode dumps are loaded in, which `show` and `query` need on their own.
+    fn into_config(self) -> Result<(GenConfig, LoadMode), String> {
+        let mut config = GenConfig::new();
+        if let Some(imports) = self.imports {
+            config = config.imports(imports);
+        }
+        for derive in self.derives {
+            config = config.derive(derive);
+        }
+        for (default, name) in self.module_names {
+            config = config.module_name(default, name);
+        }
+        if let Some(layout) = self.layout {
+            config = config.layout(match layout {
+                Layout::Files => OutputLayout::Files,
+                Layout::FilesWithMod => OutputLayout::FilesWithMod,
+                Layout::SingleFile(file_name) => OutputLayout::SingleFile(file_name),
+            });
+        }
+        if let Some(sections) = self.sections {
+            for section in Section::ALL {
+                config = config.disable(section);
+            }
+            for section in sections {
+                config = config.enable(section.parse::<Section>()?);
+            }
+        }
+        if let Some(rank) = self.max_rank {
+            config = config.max_rank(rank.parse::<Rank>()?);
+        }
+        let load_mode = self.load_mode.map(|mode| mode.parse::<LoadMode>()).transpose()?.unwrap_or_default();
+        Ok((config.load_mode(load_mode), load_mode))
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path, err))
+}
+
+fn load_config(args: &Args) -> Result<(GenConfig, LoadMode), String> {
+    let Some(path) = args.value("config") else {
+        return Ok((GenConfig::new(), LoadMode::default()));
+    };
+    let file = serde_json::from_str::<ConfigFile>(&read(path)?).map_err(|err| format!("invalid config {}: {}", path, err))?;
+    file.into_config().map_err(|err| format!("invalid config {}: {}", path, err))
+}
+
+fn load_dump(path: &str, mode: LoadMode) -> Result<ActionDump, String> {
+    mode.from_str(&read(path)?).map_err(|err| format!("failed to parse {}: {}", path, err))
+}
+
+/// Runs the subcommand, returning whether it succeeded.
+fn run(args: &Args) -> Result<bool, Error> {
+    let (config, load_mode) = load_config(args)?;
+    match args.positional.first().map(String::as_str) {
+        Some("generate") => {
+            let [dump, out_dir] = args.expect()?;
+            fs::create_dir_all(out_dir).map_err(|err| format!("failed to create {}: {}", out_dir, err))?;
+            let report = gen::gen_with_config(Path::new(dump), Path::new(out_dir), &config).map_err(|err| err.to_string())?;
+            let actions = report.modules.iter().map(|module| module.actions).sum::<usize>();
+            println!("Generated {} modules with {} actions", report.modules.len(), actions);
+            Ok(true)
+        }
+        Some("validate") => {
+            let [dump] = args.expect()?;
+            let config = if args.flag("strict") { config.load_mode(LoadMode::Strict) } else { config };
+            let contents = read(dump)?;
+            let report = drift::detect_str::<ActionDump>(&contents).map_err(|err| format!("failed to parse {}: {}", dump, err))?;
+            if !report.is_empty() {
+                print!("{}", report);
+            }
+            match gen::generate_from_str(&contents, &config) {
+                Ok(generated) => {
+                    let actions = generated.modules.iter().map(|module| module.actions).sum::<usize>();
+                    println!("{} is valid, {} actions", dump, actions);
+                    Ok(true)
+                }
+                Err(err) => {
+                    println!("{} is invalid: {}", dump, err);
+                    Ok(false)
+                }
+            }
+        }
+        Some("diff") => {
+            let [old, new] = args.expect()?;
+            // Older dumps may lack fields added since, which shouldn't stop them being compared.
+            let (old, new) = (load_dump(old, LoadMode::Lenient)?, load_dump(new, LoadMode::Lenient)?);
+            if args.flag("api") {
+                print!("{}", gen::api_changes(&old, &new, &config).map_err(|err| err.to_string())?);
+            }
+            else {
+                print!("{}", diff(&old, &new).to_markdown());
+            }
+            Ok(true)
+        }
+        Some("show") => {
+            let [name] = args.expect()?;
+            let dump = load_dump(args.value("dump").unwrap_or("actiondump.json"), load_mode)?;
+            let descriptions = gen::describe_action(&dump, &config, name).map_err(|err| err.to_string())?;
+            if descriptions.is_empty() {
+                println!("No action named {:?}", name);
+                return Ok(false);
+            }
+            println!("{}", descriptions.join("\n"));
+            Ok(true)
+        }
+        Some("query") => {
+            let [] = args.expect()?;
+            let dump = load_dump(args.value("dump").unwrap_or("actiondump.json"), load_mode)?;
+            let mut query = Query::new();
+            if let Some(codeblock) = args.value("codeblock") {
+                query = query.codeblock(codeblock);
+            }
+            if let Some(name) = args.value("name") {
+                query = query.name(name);
+            }
+            if let Some(arg_type) = args.value("arg-type") {
+                query = query.arg_type(arg_type);
+            }
+            if let Some(tag) = args.value("tag") {
+                query = query.tag(tag);
+            }
+            if let Some(option) = args.value("tag-option") {
+                query = query.tag_option(option);
+            }
+            if let Some(rank) = args.value("rank") {
+                query = query.rank(rank.parse::<Rank>().map_err(Error::Usage)?);
+            }
+            let actions = query.run(&dump);
+            for action in &actions {
+                println!("{}: {}", action.codeblock_name, action.name);
+            }
+            Ok(!actions.is_empty())
+        }
+        Some(command) => Err(Error::Usage(format!("unknown command {:?}", command))),
+        None => Err(Error::Usage("no command given".to_string())),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) if args.flag("help") || args.positional.first().is_some_and(|command| command == "help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(Error::Usage(err)) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            ExitCode::from(2)
+        }
+        Err(Error::Failed(err)) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Searches a dump's actions, eg. for every action taking a particle or with a given tag.
+
+use crate::{gen::Rank, raw_types::{Action, ActionArgOptions, ActionDump}};
+
+/// A search over a dump's actions, built up from [`Query::new`]. An action matches if it meets every condition given,
+/// names are compared ignoring case.
+///
+/// ```
+/// use saphire_typegen::{gen::Rank, query::Query};
+///
+/// let query = Query::new()
+///     .codeblock("PLAYER ACTION")
+///     .arg_type("PARTICLE")
+///     .rank(Rank::None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    codeblock: Option<String>,
+    name: Option<String>,
+    arg_type: Option<String>,
+    tag: Option<String>,
+    tag_option: Option<String>,
+    rank: Option<Rank>,
+}
+
+impl Query {
+    /// A query matching every action.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only actions of the codeblock with this name, eg. "PLAYER ACTION".
+    pub fn codeblock<S: Into<String>>(mut self, codeblock: S) -> Self {
+        self.codeblock = Some(codeblock.into());
+        self
+    }
+
+    /// Only actions with this name or alias, eg. "SendMessage" or "Message".
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only actions taking an argument of this type, eg. "PARTICLE".
+    pub fn arg_type<S: Into<String>>(mut self, arg_type: S) -> Self {
+        self.arg_type = Some(arg_type.into());
+        self
+    }
+
+    /// Only actions with a tag of this name, eg. "Ignore Formatting".
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only actions with a tag option of this name or alias. Along with [`tag`](Query::tag), the option must be one of that tag's.
+    pub fn tag_option<S: Into<String>>(mut self, option: S) -> Self {
+        self.tag_option = Some(option.into());
+        self
+    }
+
+    /// Only actions that need exactly this rank.
+    pub fn rank(mut self, rank: Rank) -> Self {
+        self.rank = Some(rank);
+        self
+    }
+
+    /// Whether `action` meets every condition of the query.
+    pub fn matches(&self, action: &Action) -> bool {
+        let is = |expected: &Option<String>, found: &str| expected.as_ref().is_none_or(|expected| expected.eq_ignore_ascii_case(found));
+
+        let mut names = std::iter::once(&action.name).chain(&action.aliases);
+        let mut arg_types = action.icon.arguments().iter().filter_map(|arg| match arg {
+            ActionArgOptions::Arg(arg) => Some(&arg.arg_type),
+            ActionArgOptions::Text { .. } => None,
+        });
+        let tags = action.tags.iter().filter(|tag| is(&self.tag, &tag.name)).collect::<Vec<_>>();
+        let mut options = tags.iter()
+            .flat_map(|tag| &tag.options)
+            .flat_map(|option| std::iter::once(&option.name).chain(&option.aliases));
+        let rank = action.icon.required_rank.parse::<Rank>().ok();
+
+        is(&self.codeblock, &action.codeblock_name)
+            && (self.name.is_none() || names.any(|name| is(&self.name, name)))
+            && (self.arg_type.is_none() || arg_types.any(|arg_type| is(&self.arg_type, arg_type)))
+            && (self.tag.is_none() || !tags.is_empty())
+            && (self.tag_option.is_none() || options.any(|option| is(&self.tag_option, option)))
+            && self.rank.is_none_or(|expected| rank == Some(expected))
+    }
+
+    /// Every action of `dump` the query matches, in the order they're listed.
+    pub fn run<'a>(&self, dump: &'a ActionDump) -> Vec<&'a Action> {
+        dump.actions.iter().filter(|action| self.matches(action)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ACTION_DUMP: &str = include_str!("../test_data/actiondump.json");
+
+    fn names(query: Query) -> Vec<String> {
+        let dump = serde_json::from_str::<ActionDump>(ACTION_DUMP).unwrap();
+        query.run(&dump).into_iter().map(|action| action.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_query() {
+        assert_eq!(names(Query::new()).len(), 11);
+        assert_eq!(names(Query::new().codeblock("player action")), ["SendMessage", "SetReducedDebug"]);
+        assert_eq!(names(Query::new().name("message")), ["SendMessage"]);
+        assert_eq!(names(Query::new().arg_type("NUMBER")), ["IsNear", "+", "Wait"]);
+        assert_eq!(names(Query::new().arg_type("NUMBER").codeblock("CONTROL")), ["Wait"]);
+        assert_eq!(names(Query::new().tag("Alignment Mode")), ["SendMessage"]);
+        assert_eq!(names(Query::new().tag_option("Seconds")), ["Wait"]);
+        assert!(names(Query::new().tag("Alignment Mode").tag_option("Seconds")).is_empty());
+        assert_eq!(names(Query::new().rank(Rank::Noble)), ["SetReducedDebug"]);
+        assert!(names(Query::new().name("NotAnAction")).is_empty());
+    }
+}
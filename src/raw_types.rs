@@ -1,15 +1,61 @@
-use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use serde::{de::{self, DeserializeOwned}, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use crate::load::{default_fields, RawType, Walker};
+
+pub use crate::load::LoadMode;
+
+/// The entire actiondump, every section of the file is deserialized into its own list.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDump {
+    pub codeblocks: Vec<CodeBlock>,
+    pub actions: Vec<Action>,
+    pub game_value_categories: Vec<GameValueCategory>,
+    pub game_values: Vec<GameValue>,
+    pub particle_categories: Vec<ParticleCategory>,
+    pub particles: Vec<Particle>,
+    pub sound_categories: Vec<SoundCategory>,
+    pub sounds: Vec<Sound>,
+    pub potions: Vec<Potion>,
+    pub cosmetics: Vec<Cosmetic>,
+    pub shops: Vec<Shop>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Action {
     pub name: String,
     pub codeblock_name: String,
+    #[serde(default)]
     pub tags: Vec<ActionTags>,
+    #[serde(default)]
     pub aliases: Vec<String>,
-    pub icon: ActionIconOptions,
+    pub icon: ActionIcon,
     #[serde(default)]
-    pub sub_action_blocks: Vec<String>
+    pub sub_action_blocks: Vec<String>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+impl Action {
+    /// The name shared by dynamic actions, ie. "call function" and "start process".
+    pub const DYNAMIC: &'static str = "dynamic";
+
+    /// Whether this is a dynamic action, these run a function or process picked by name instead of having arguments.
+    pub fn is_dynamic(&self) -> bool {
+        self.name == Self::DYNAMIC
+    }
+
+    /// The tag count on the action's icon and the number of tags it actually has, if they disagree.
+    /// A mismatch usually means the dump was truncated or edited by hand.
+    pub fn tag_count_mismatch(&self) -> Option<(usize, usize)> {
+        self.icon.tag_count()
+            .filter(|count| *count != self.tags.len())
+            .map(|count| (count, self.tags.len()))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -20,6 +66,8 @@ pub struct ActionTags {
     pub default_option: String,
     pub slot: usize,
     //pub aliases: Vec<String>, // apparently this was removed, leaving it here incase it comes back
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -28,76 +76,141 @@ pub struct ActionTagOption {
     pub name: String,
     pub icon: ActionTagIcon,
     pub aliases: Vec<String>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct ActionTagIcon {
-    pub material: String,
-    pub name: String,
-    pub deprecated_note: Vec<String>,
-    pub description: Vec<String>,
-    pub example: Vec<String>,
-    pub works_with: Vec<String>,
-    pub additional_info: Vec<Vec<String>>,
-    pub required_rank: String,
-    pub require_tokens: bool,
-    pub require_rank_and_tokens: bool,
-    pub advanced: bool,
-    pub loaded_item: String,
+/// The icon of a tag option.
+pub type ActionTagIcon = IconBase;
+
+/// The icon of an action, the fields every icon has along with the arguments of actions that take any.
+///
+/// Events have no argument data and dynamic actions' icons only have a material and name,
+/// the rest of their base fields are left empty.
+#[derive(Serialize, Debug, Default)]
+pub struct ActionIcon {
+    #[serde(flatten)]
+    pub base: IconBase,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub args: Option<ActionArgData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum ActionIconOptions {
-    Icon (
-        ActionIcon,
-    ),
-    Event (
-        ActionEventIcon,
-    ),
-}
-
-impl Default for ActionIconOptions {
-    fn default() -> Self {
-        ActionIconOptions::Event(ActionEventIcon::default())
+/// The name `Action::icon` had when icons were split by kind, see [`ActionIcon`].
+pub type ActionIconOptions = ActionIcon;
+
+#[deprecated(note = "event icons are an `ActionIcon` without `args`, their fields are in `IconBase`")]
+pub type ActionEventIcon = IconBase;
+
+impl ActionIcon {
+    /// The arguments the action takes, empty for events and dynamic actions.
+    pub fn arguments(&self) -> &[ActionArgOptions] {
+        self.args.as_ref().map_or(&[], |args| &args.arguments)
+    }
+
+    /// The types of value the action sets its output variable to.
+    pub fn return_values(&self) -> &[ActionIconReturnTypeOption] {
+        self.args.as_ref().map_or(&[], |args| &args.return_values)
+    }
+
+    /// How many tags the action has, according to the icon.
+    pub fn tag_count(&self) -> Option<usize> {
+        self.args.as_ref().and_then(|args| args.tags)
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct ActionEventIcon {
-    pub material: String,
-    pub name: String,
-    pub deprecated_note: Vec<String>,
-    pub description: Vec<String>,
-    pub example: Vec<String>,
-    pub works_with: Vec<String>,
-    pub additional_info: Vec<Vec<String>>,
-    pub required_rank: String,
-    pub require_tokens: bool,
-    pub require_rank_and_tokens: bool,
-    pub advanced: bool,
-    pub loaded_item: String,
+impl Deref for ActionIcon {
+    type Target = IconBase;
+
+    fn deref(&self) -> &IconBase {
+        &self.base
+    }
+}
+
+impl DerefMut for ActionIcon {
+    fn deref_mut(&mut self) -> &mut IconBase {
+        &mut self.base
+    }
 }
 
+impl<'de> Deserialize<'de> for ActionIcon {
+    /// Reads each field on its own, so a malformed icon reports the field at fault rather than matching no variant.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::<String, Value>::deserialize(deserializer)?;
+        let mut field = |name: &'static str| fields.remove(name);
+        let material = field("material");
+        let name = field("name");
+        let base = [
+            "deprecatedNote", "description", "example", "worksWith", "additionalInfo",
+            "requiredRank", "requireTokens", "requireRankAndTokens", "advanced", "loadedItem",
+        ].map(|name| (name, field(name)));
+        let tags = field("tags");
+        let arguments = field("arguments");
+        let return_values = field("returnValues");
+
+        // Dynamic actions' icons only have a material and name, any other icon needs every base field.
+        let minimal = base.iter().all(|(_, value)| value.is_none());
+        let [deprecated_note, description, example, works_with, additional_info, required_rank, require_tokens, require_rank_and_tokens, advanced, loaded_item] = base;
+        let base = IconBase {
+            material: required_field("material", material)?,
+            name: required_field("name", name)?,
+            deprecated_note: base_field(deprecated_note, minimal)?,
+            description: base_field(description, minimal)?,
+            example: base_field(example, minimal)?,
+            works_with: base_field(works_with, minimal)?,
+            additional_info: base_field(additional_info, minimal)?,
+            required_rank: base_field(required_rank, minimal)?,
+            require_tokens: base_field(require_tokens, minimal)?,
+            require_rank_and_tokens: base_field(require_rank_and_tokens, minimal)?,
+            advanced: base_field(advanced, minimal)?,
+            loaded_item: base_field(loaded_item, minimal)?,
+            extra: fields,
+        };
+
+        let args = if tags.is_none() && arguments.is_none() && return_values.is_none() {
+            None
+        }
+        else {
+            Some(ActionArgData {
+                tags: optional_field("tags", tags)?,
+                arguments: optional_field("arguments", arguments)?.unwrap_or_default(),
+                return_values: optional_field("returnValues", return_values)?.unwrap_or_default(),
+            })
+        };
+
+        Ok(ActionIcon { base, args })
+    }
+}
+
+/// Deserializes a field that may be missing, naming it in the error if it's malformed.
+fn optional_field<T: DeserializeOwned, E: de::Error>(name: &'static str, value: Option<Value>) -> Result<Option<T>, E> {
+    value
+        .map(|value| serde_json::from_value(value).map_err(|err| E::custom(format_args!("invalid `{}`: {}", name, err))))
+        .transpose()
+}
+
+/// Deserializes a field that must be present.
+fn required_field<T: DeserializeOwned, E: de::Error>(name: &'static str, value: Option<Value>) -> Result<T, E> {
+    optional_field(name, value)?.ok_or_else(|| E::missing_field(name))
+}
+
+/// Deserializes one of an icon's base fields, which may only be missing if every base field is.
+fn base_field<T: DeserializeOwned + Default, E: de::Error>((name, value): (&'static str, Option<Value>), minimal: bool) -> Result<T, E> {
+    if minimal {
+        return Ok(T::default());
+    }
+    required_field(name, value)
+}
+
+/// The parts of an icon only actions that take arguments have.
 #[derive(Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct ActionIcon {
-    pub material: String,
-    pub name: String,
-    pub deprecated_note: Vec<String>,
-    pub description: Vec<String>,
-    pub example: Vec<String>,
-    pub works_with: Vec<String>,
-    pub additional_info: Vec<Vec<String>>,
-    pub required_rank: String,
-    pub require_tokens: bool,
-    pub require_rank_and_tokens: bool,
-    pub advanced: bool,
-    pub loaded_item: String,
-    //pub tags: usize, // TODO: change action_icon to be an enum for support of icons that dont have tags or arguments
+pub struct ActionArgData {
+    /// How many tags the action has, according to the icon. Should match `Action::tags`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<usize>,
+    #[serde(default)]
     pub arguments: Vec<ActionArgOptions>,
+    #[serde(default)]
     pub return_values: Vec<ActionIconReturnTypeOption>,
 }
 
@@ -120,6 +233,8 @@ pub struct ActionArg {
     pub optional: bool,
     pub description: Vec<String>,
     pub notes: Vec<Vec<String>>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -138,6 +253,270 @@ pub struct ActionIconReturnType {
     #[serde(rename = "type")]
     pub type_name: String,
     pub description: Vec<String>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+/// The fields every item in the dump has, shared by action and tag icons and the items
+/// shown for codeblocks, categories, particles, sounds, potions and cosmetics.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IconBase {
+    pub material: String,
+    pub name: String,
+    pub deprecated_note: Vec<String>,
+    pub description: Vec<String>,
+    pub example: Vec<String>,
+    pub works_with: Vec<String>,
+    pub additional_info: Vec<Vec<String>>,
+    pub required_rank: String,
+    pub require_tokens: bool,
+    pub require_rank_and_tokens: bool,
+    pub advanced: bool,
+    pub loaded_item: String,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+/// A kind of codeblock, actions refer to it by `name` while templates use the `identifier`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock {
+    /// The name actions use in `codeblockName`, eg. "PLAYER ACTION".
+    pub name: String,
+    /// The identifier used in template json, eg. "player_action".
+    pub identifier: String,
+    pub item: IconBase,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameValueCategory {
+    pub identifier: String,
+    pub gui_slot: usize,
+    pub icon: IconBase,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameValue {
+    pub aliases: Vec<String>,
+    pub category: String,
+    pub icon: GameValueIcon,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+/// A game value's icon, the fields every item has along with what the game value returns.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameValueIcon {
+    #[serde(flatten)]
+    pub base: IconBase,
+    pub return_type: String,
+    pub return_description: Vec<String>,
+}
+
+impl Deref for GameValueIcon {
+    type Target = IconBase;
+
+    fn deref(&self) -> &IconBase {
+        &self.base
+    }
+}
+
+impl DerefMut for GameValueIcon {
+    fn deref_mut(&mut self) -> &mut IconBase {
+        &mut self.base
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticleCategory {
+    pub identifier: String,
+    pub gui_slot: usize,
+    pub icon: IconBase,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Particle {
+    pub particle: String,
+    pub icon: IconBase,
+    pub category: Option<String>,
+    pub fields: Vec<String>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SoundCategory {
+    pub identifier: String,
+    pub icon: IconBase,
+    pub has_sub_categories: bool,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Sound {
+    pub sound: String,
+    pub icon: IconBase,
+    pub category: Option<String>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Potion {
+    pub potion: String,
+    pub icon: IconBase,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Cosmetic {
+    pub id: String,
+    pub icon: IconBase,
+    pub name: String,
+    pub category: CosmeticCategory,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CosmeticCategory {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Shop {
+    pub id: String,
+    pub slot: Option<usize>,
+    pub name: Option<String>,
+    pub purchasables: Vec<ShopPurchasable>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShopPurchasable {
+    pub item: IconBase,
+    pub id: Option<String>,
+    pub price: Option<u64>,
+    pub currency_type: Option<String>,
+    pub one_time_purchase: Option<bool>,
+    #[serde(flatten, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+/// Implements [`RawType`] for structs, along with the json name and type of the fields holding other raw types
+/// and, in brackets, the fields serde defaults when they're missing.
+macro_rules! raw_types {
+    ($($raw_type:ident { $($field:literal => $walk:ident<$nested:ty>),* $(,)? } $([$($optional:literal),*])?)*) => {
+        $(
+            impl RawType for $raw_type {
+                fn walk(value: &mut Value, walker: &mut Walker) {
+                    #[allow(unused_variables)]
+                    walker.object(value, stringify!($raw_type), default_fields::<Self>(), &[$($($optional),*)?], |fields, walker| {
+                        $(walker.$walk::<$nested>(fields, $field);)*
+                    });
+                }
+            }
+        )*
+    };
+}
+
+raw_types! {
+    ActionDump {
+        "codeblocks" => list<CodeBlock>,
+        "actions" => list<Action>,
+        "gameValueCategories" => list<GameValueCategory>,
+        "gameValues" => list<GameValue>,
+        "particleCategories" => list<ParticleCategory>,
+        "particles" => list<Particle>,
+        "soundCategories" => list<SoundCategory>,
+        "sounds" => list<Sound>,
+        "potions" => list<Potion>,
+        "cosmetics" => list<Cosmetic>,
+        "shops" => list<Shop>,
+    }
+    Action { "tags" => list<ActionTags>, "icon" => field<ActionIcon> } ["tags", "aliases", "subActionBlocks"]
+    ActionTags { "options" => list<ActionTagOption> }
+    ActionTagOption { "icon" => field<IconBase> }
+    ActionArg {}
+    ActionIconReturnType {}
+    IconBase {}
+    CodeBlock { "item" => field<IconBase> }
+    GameValueCategory { "icon" => field<IconBase> }
+    GameValue { "icon" => field<GameValueIcon> }
+    GameValueIcon {}
+    ParticleCategory { "icon" => field<IconBase> }
+    Particle { "icon" => field<IconBase> }
+    SoundCategory { "icon" => field<IconBase> }
+    Sound { "icon" => field<IconBase> }
+    Potion { "icon" => field<IconBase> }
+    Cosmetic { "icon" => field<IconBase>, "category" => field<CosmeticCategory> }
+    CosmeticCategory {}
+    Shop { "purchasables" => list<ShopPurchasable> }
+    ShopPurchasable { "item" => field<IconBase> }
+}
+
+impl RawType for ActionIcon {
+    /// The argument data is left out rather than defaulted, so a lenient event icon doesn't gain any.
+    /// Dynamic actions' icons may also leave out every base field but the material and name.
+    fn walk(value: &mut Value, walker: &mut Walker) {
+        let mut optional = vec!["tags", "arguments", "returnValues"];
+        let minimal = value.as_object().is_some_and(|fields| fields.keys().all(|name| name == "material" || name == "name"));
+        let defaults = default_fields::<Self>();
+        if minimal {
+            optional.extend(defaults.keys().map(String::as_str).filter(|name| *name != "material" && *name != "name"));
+        }
+        walker.object(value, "ActionIcon", defaults.clone(), &optional, |fields, walker| {
+            walker.list::<ActionArgOptions>(fields, "arguments");
+            walker.list::<ActionIconReturnTypeOption>(fields, "returnValues");
+        });
+    }
+}
+
+impl RawType for ActionArgOptions {
+    fn walk(value: &mut Value, walker: &mut Walker) {
+        if value.get("text").is_some() {
+            walker.object(value, "ActionArgOptions::Text", Map::from_iter([("text".to_string(), Value::from(""))]), &[], |_, _| {});
+        }
+        else {
+            ActionArg::walk(value, walker);
+        }
+    }
+}
+
+impl RawType for ActionIconReturnTypeOption {
+    fn walk(value: &mut Value, walker: &mut Walker) {
+        if value.get("text").is_some() {
+            walker.object(value, "ActionIconReturnTypeOption::Text", Map::from_iter([("text".to_string(), Value::from(""))]), &[], |_, _| {});
+        }
+        else {
+            ActionIconReturnType::walk(value, walker);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -222,13 +601,17 @@ mod test {
         }
         "#;
 
-        let action = serde_json::from_str::<Action>(json).unwrap();
+        let mut action = serde_json::from_str::<Action>(json).unwrap();
         println!("{:#?}", action);
+        assert_eq!(action.tag_count_mismatch(), None);
+
+        action.tags.clear();
+        assert_eq!(action.tag_count_mismatch(), Some((1, 0)));
     }
 
     #[test]
     fn test_action_icon() {
-        let encoded = serde_json::to_string_pretty(&ActionIconOptions::default()).unwrap();
+        let encoded = serde_json::to_string_pretty(&ActionIcon::default()).unwrap();
         println!("{}", encoded);
         let json = r#"
         {
@@ -265,8 +648,22 @@ mod test {
             ]
         }
         "#;
-        let action_icon = serde_json::from_str::<ActionIconOptions>(json).unwrap();
+        let action_icon = serde_json::from_str::<ActionIcon>(json).unwrap();
         println!("{:#?}", action_icon);
+        assert_eq!(action_icon.name, "Set Hotbar Items");
+        assert_eq!(action_icon.arguments().len(), 1);
+    }
+
+    #[test]
+    fn test_action_icon_errors() {
+        let missing = serde_json::from_str::<ActionIcon>(r#"{ "material": "DROPPER", "name": "Event", "description": [] }"#).unwrap_err();
+        assert!(missing.to_string().contains("missing field `deprecatedNote`"), "{}", missing);
+
+        let invalid = serde_json::from_str::<ActionIcon>(r#"{ "material": "DROPPER", "name": "Event", "tags": "one" }"#).unwrap_err();
+        assert!(invalid.to_string().contains("invalid `tags`"), "{}", invalid);
+
+        let no_name = serde_json::from_str::<ActionIcon>(r#"{ "material": "DROPPER" }"#).unwrap_err();
+        assert!(no_name.to_string().contains("missing field `name`"), "{}", no_name);
     }
 
     #[test]
@@ -282,4 +679,79 @@ mod test {
         let action_args = serde_json::from_str::<ActionArgOptions>(json).unwrap();
         println!("{:#?}", action_args);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_action_dump() {
+        let json = include_str!("../test_data/actiondump.json");
+        let dump = serde_json::from_str::<ActionDump>(json).unwrap();
+        assert_eq!(dump.codeblocks.len(), 18);
+        assert_eq!(dump.game_values[0].icon.return_type, "NUMBER");
+        assert_eq!(dump.game_values[0].icon.name, "Current Health");
+        assert!(dump.game_values[0].icon.extra.is_empty());
+        assert_eq!(dump.particles[1].fields.len(), 6);
+        assert_eq!(dump.shops[0].purchasables[0].price, Some(100));
+    }
+
+    #[test]
+    fn test_dynamic_action() {
+        let json = r#"
+        {
+            "name": "dynamic",
+            "codeblockName": "START PROCESS",
+            "tags": [],
+            "aliases": [],
+            "icon": {
+                "material": "EMERALD_ORE",
+                "name": ""
+            }
+        }
+        "#;
+
+        let action = serde_json::from_str::<Action>(json).unwrap();
+        assert!(action.is_dynamic());
+        assert!(action.icon.args.is_none());
+        assert_eq!(action.icon.material, "EMERALD_ORE");
+    }
+
+    #[test]
+    fn test_load_modes() {
+        let json = r#"
+        {
+            "name": "SetReducedDebug",
+            "codeblockName": "PLAYER ACTION",
+            "shiny": true,
+            "icon": {
+                "material": "DROPPER",
+                "name": "Set Reduced Debug Info",
+                "description": [],
+                "arguments": [
+                    {
+                        "type": "NUMBER",
+                        "plural": false,
+                        "optional": false,
+                        "description": [],
+                        "notes": [],
+                        "range": [0, 1]
+                    }
+                ]
+            }
+        }
+        "#;
+
+        let missing = LoadMode::Standard.from_str::<Action>(json).unwrap_err();
+        assert!(missing.to_string().contains("missing field `deprecatedNote`"), "{}", missing);
+
+        let unknown = LoadMode::Strict.from_str::<Action>(json).unwrap_err();
+        assert!(unknown.to_string().contains("`shiny`, `icon.arguments[0].range`"), "{}", unknown);
+
+        let action = LoadMode::Lenient.from_str::<Action>(json).unwrap();
+        assert_eq!(action.extra.get("shiny"), Some(&Value::Bool(true)));
+        assert!(action.icon.example.is_empty());
+        match &action.icon.arguments()[0] {
+            ActionArgOptions::Arg(arg) => assert_eq!(arg.extra.get("range"), Some(&serde_json::json!([0, 1]))),
+            other => panic!("expected an argument, found {:?}", other),
+        }
+        // The icon's argument data is kept as it is rather than filled in.
+        assert_eq!(action.icon.tag_count(), None);
+    }
+}
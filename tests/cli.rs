@@ -0,0 +1,69 @@
+use std::process::Command;
+
+const DUMP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/actiondump.json");
+
+fn run(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_saphire-typegen")).args(args).output().unwrap();
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn test_validate() {
+    let (code, stdout) = run(&["validate", DUMP, "--strict"]);
+    assert_eq!(code, 0, "{}", stdout);
+    assert!(stdout.contains("is valid"));
+}
+
+#[test]
+fn test_show() {
+    let (code, stdout) = run(&["show", "SendMessage", "--dump", DUMP]);
+    assert_eq!(code, 0);
+    assert!(stdout.contains("SendMessage {"));
+    assert!(stdout.contains("pub enum AlignmentMode"));
+
+    let (code, _) = run(&["show", "NotAnAction", "--dump", DUMP]);
+    assert_eq!(code, 1);
+}
+
+#[test]
+fn test_config_load_mode() {
+    let dir = std::env::temp_dir().join(format!("saphire-typegen-cli-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut dump = serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(DUMP).unwrap()).unwrap();
+    dump.as_object_mut().unwrap().remove("shops");
+    let dump_path = dir.join("actiondump.json");
+    std::fs::write(&dump_path, dump.to_string()).unwrap();
+    let config_path = dir.join("config.json");
+    std::fs::write(&config_path, r#"{ "loadMode": "Lenient" }"#).unwrap();
+    let (dump, config) = (dump_path.to_str().unwrap(), config_path.to_str().unwrap());
+
+    assert_eq!(run(&["show", "SendMessage", "--dump", dump]).0, 1);
+    assert_eq!(run(&["show", "SendMessage", "--dump", dump, "--config", config]).0, 0);
+    assert_eq!(run(&["query", "--dump", dump, "--name", "Wait"]).0, 1);
+    assert_eq!(run(&["query", "--dump", dump, "--name", "Wait", "--config", config]).0, 0);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_diff() {
+    let (code, stdout) = run(&["diff", DUMP, DUMP]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "No changes to actions.\n");
+}
+
+#[test]
+fn test_query() {
+    let (code, stdout) = run(&["query", "--dump", DUMP, "--arg-type", "NUMBER", "--codeblock", "CONTROL"]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "CONTROL: Wait\n");
+
+    assert_eq!(run(&["query", "--dump", DUMP, "--tag", "Not A Tag"]).0, 1);
+    assert_eq!(run(&["query", "--dump", DUMP, "--rank", "Admin"]).0, 2);
+}
+
+#[test]
+fn test_usage() {
+    assert_eq!(run(&["frobnicate"]).0, 2);
+    assert_eq!(run(&["diff", DUMP]).0, 2);
+    assert_eq!(run(&["validate", DUMP, "--verbose"]).0, 2);
+}